@@ -0,0 +1,163 @@
+use crate::CliError;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use proj::{Area, AreaOfUse, CoordinateOperation, Proj, ProjBuilder};
+use std::io::{self, Write};
+
+/// The info subcommand
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("info")
+        .about("Describe a CRS, and list the candidate operations to another CRS")
+        .arg(
+            Arg::with_name("crs")
+                .value_name("CRS")
+                .help("The CRS, e.g. EPSG:32633, or any other definition accepted by PROJ")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("to")
+                .long("to")
+                .short("t")
+                .value_name("CRS")
+                .help("List the candidate operations from CRS to this CRS")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("area")
+                .long("area")
+                .value_name("WEST,SOUTH,EAST,NORTH")
+                .help("Only list operations whose area of use intersects this bounding box, in degrees")
+                .takes_value(true)
+                .requires("to"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .value_name("FORMAT")
+                .help("The format of the definition of the CRS")
+                .takes_value(true)
+                .possible_values(&["wkt", "projjson", "none"])
+                .default_value("wkt"),
+        )
+}
+
+fn parse_area(area: &str) -> Result<Area, CliError> {
+    let bounds = area
+        .split(',')
+        .map(|bound| bound.trim().parse())
+        .collect::<Result<Vec<f64>, _>>()
+        .ok()
+        .filter(|bounds| bounds.len() == 4)
+        .ok_or_else(|| {
+            CliError::Usage(format!(
+                "the area must be given as WEST,SOUTH,EAST,NORTH, not {}",
+                area
+            ))
+        })?;
+    Ok(Area::new(bounds[0], bounds[1], bounds[2], bounds[3]))
+}
+
+/// Describe the CRS and, if a target CRS is given, the candidate operations to it
+pub fn run(matches: &ArgMatches) -> Result<(), CliError> {
+    let definition = matches.value_of("crs").unwrap();
+    let crs = Proj::new(definition)
+        .filter(|crs| crs.crs_type().is_some())
+        .ok_or_else(|| CliError::Usage(format!("{} isn't a CRS", definition)))?;
+    let area = match matches.value_of("area") {
+        Some(area) => Some(parse_area(area)?),
+        None => None,
+    };
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    describe(&crs, &mut out)?;
+    let exported = match matches.value_of("format").unwrap() {
+        "wkt" => Some(("WKT", crs.to_wkt())),
+        "projjson" => Some(("PROJJSON", crs.to_projjson())),
+        _ => None,
+    };
+    if let Some((format, exported)) = exported {
+        let exported = exported.ok_or_else(|| {
+            CliError::Usage(format!("{} can't be exported as {}", definition, format))
+        })?;
+        writeln!(out, "\n{}:\n{}", format, exported)?;
+    }
+    if let Some(to) = matches.value_of("to") {
+        let operations = ProjBuilder::new().candidate_operations(definition, to, area)?;
+        writeln!(out)?;
+        list_operations(definition, to, &operations, &mut out)?;
+    }
+    Ok(())
+}
+
+fn write_area_of_use<W: Write>(
+    area_of_use: &AreaOfUse,
+    indent: &str,
+    out: &mut W,
+) -> io::Result<()> {
+    if let Some(name) = &area_of_use.name {
+        writeln!(out, "{}Area of use: {}", indent, name)?;
+    }
+    let area = area_of_use.area;
+    writeln!(
+        out,
+        "{}Bounds: west {}, south {}, east {}, north {}",
+        indent,
+        area.west(),
+        area.south(),
+        area.east(),
+        area.north()
+    )
+}
+
+fn describe<W: Write>(crs: &Proj, out: &mut W) -> io::Result<()> {
+    writeln!(out, "Name: {}", crs.name().unwrap_or_default())?;
+    if let Some(crs_type) = crs.crs_type() {
+        writeln!(out, "Type: {}", crs_type)?;
+    }
+    if crs.is_deprecated() {
+        writeln!(out, "Deprecated: yes")?;
+    }
+    if let Some(area_of_use) = crs.area_of_use() {
+        write_area_of_use(&area_of_use, "", out)?;
+    }
+    let axes = crs.axes();
+    if !axes.is_empty() {
+        writeln!(out, "Axes:")?;
+        for axis in axes {
+            writeln!(
+                out,
+                "  {} ({}): {}, {}",
+                axis.name, axis.abbreviation, axis.direction, axis.unit.name
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn list_operations<W: Write>(
+    from: &str,
+    to: &str,
+    operations: &[CoordinateOperation],
+    out: &mut W,
+) -> io::Result<()> {
+    if operations.is_empty() {
+        return writeln!(out, "No candidate operations from {} to {}", from, to);
+    }
+    writeln!(out, "Candidate operations from {} to {}:", from, to)?;
+    for (i, operation) in operations.iter().enumerate() {
+        writeln!(out, "{}. {}", i + 1, operation.name)?;
+        match operation.accuracy {
+            Some(accuracy) => writeln!(out, "   Accuracy: {} m", accuracy)?,
+            None => writeln!(out, "   Accuracy: unknown")?,
+        }
+        if !operation.instantiable {
+            writeln!(out, "   Available: no, its grids are missing")?;
+        }
+        if let Some(area_of_use) = &operation.area_of_use {
+            write_area_of_use(area_of_use, "   ", out)?;
+        }
+        if let Some(proj_string) = &operation.proj_string {
+            writeln!(out, "   PROJ string: {}", proj_string)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! `proj-rs`: transform coordinates between coordinate reference systems
+//!
+//! Coordinates are read as delimited text from files or standard input, and written to
+//! standard output with the coordinate columns replaced by the transformed coordinates.
+//!
+//! The `info` subcommand describes a CRS and the candidate operations to another CRS, and the
+//! `search` subcommand searches the `PROJ` database for CRSs by name.
+//! Run `proj-rs --help` for the available options.
+use clap::{crate_version, App, AppSettings};
+use proj::ProjError;
+use std::io;
+use std::process;
+use thiserror::Error;
+
+mod info;
+mod search;
+mod transform;
+
+/// Errors which end a `proj-rs` run
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error(transparent)]
+    Proj(#[from] ProjError),
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("{0}")]
+    Usage(String),
+    #[error("line {line}: {message}")]
+    Record { line: u64, message: String },
+    #[error("no CRS matches {0:?}")]
+    NoMatch(String),
+}
+
+fn main() {
+    let matches = transform::args(
+        App::new("proj-rs")
+            .version(crate_version!())
+            .about("Transform coordinates between coordinate reference systems using PROJ")
+            // the transform arguments aren't required by the subcommands
+            .setting(AppSettings::SubcommandsNegateReqs)
+            .subcommand(info::command())
+            .subcommand(search::command()),
+    )
+    .get_matches();
+    let result = match matches.subcommand() {
+        ("info", Some(matches)) => info::run(matches),
+        ("search", Some(matches)) => search::run(matches),
+        _ => transform::run(&matches),
+    };
+    if let Err(err) = result {
+        eprintln!("proj-rs: {}", err);
+        process::exit(1);
+    }
+}
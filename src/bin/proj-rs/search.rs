@@ -0,0 +1,88 @@
+use crate::CliError;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use proj::CrsType;
+use std::io::{self, Write};
+
+/// The search subcommand
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("search")
+        .about("Search the PROJ database for CRSs whose identifier or name contains every word of a query")
+        .arg(
+            Arg::with_name("query")
+                .value_name("QUERY")
+                .help("The words to search for, e.g. \"UTM 33\"")
+                .required(true)
+                .multiple(true),
+        )
+        .arg(
+            Arg::with_name("authority")
+                .long("authority")
+                .short("a")
+                .value_name("AUTHORITY")
+                .help("Only search the CRSs of this authority, e.g. EPSG")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("type")
+                .long("type")
+                .value_name("TYPE")
+                .help("Only search CRSs of this type")
+                .takes_value(true)
+                .possible_values(&["geographic", "geocentric", "projected", "vertical", "compound"]),
+        )
+        .arg(
+            Arg::with_name("deprecated")
+                .long("deprecated")
+                .help("Include deprecated CRSs"),
+        )
+}
+
+fn has_type(crs_type: Option<CrsType>, wanted: &str) -> bool {
+    matches!(
+        (crs_type, wanted),
+        (Some(CrsType::Geographic2D), "geographic")
+            | (Some(CrsType::Geographic3D), "geographic")
+            | (Some(CrsType::Geocentric), "geocentric")
+            | (Some(CrsType::Projected), "projected")
+            | (Some(CrsType::Vertical), "vertical")
+            | (Some(CrsType::Compound), "compound")
+    )
+}
+
+/// Print the identifier, type and name of each matching CRS
+pub fn run(matches: &ArgMatches) -> Result<(), CliError> {
+    let query: Vec<&str> = matches.values_of("query").unwrap().collect();
+    let query = query.join(" ");
+    let crs_type = matches.value_of("type");
+    let found: Vec<_> = proj::list_crs(
+        matches.value_of("authority"),
+        matches.is_present("deprecated"),
+    )?
+    .into_iter()
+    .filter(|crs| crs.matches(&query))
+    .filter(|crs| match crs_type {
+        Some(wanted) => has_type(crs.crs_type, wanted),
+        None => true,
+    })
+    .collect();
+    if found.is_empty() {
+        return Err(CliError::NoMatch(query));
+    }
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for crs in found {
+        let crs_type = crs.crs_type.map(|crs_type| crs_type.to_string());
+        write!(
+            out,
+            "{:<16} {:<18} {}",
+            crs.id(),
+            crs_type.as_deref().unwrap_or("Unknown"),
+            crs.name
+        )?;
+        if crs.deprecated {
+            write!(out, " (deprecated)")?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,334 @@
+use crate::CliError;
+use clap::{App, Arg, ArgMatches};
+use csv::{ReaderBuilder, StringRecord, Trim, Writer, WriterBuilder};
+use proj::Proj;
+use std::fs::File;
+use std::io::{self, Read, Write};
+
+/// The number of records transformed in a single batch
+const BATCH_SIZE: usize = 1024;
+
+/// What to do with a record whose coordinates can't be parsed or transformed
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OnError {
+    /// Stop, and exit with an error
+    Fail,
+    /// Report the error, and leave the record out of the output
+    Skip,
+    /// Report the error, and write the record unchanged
+    Keep,
+}
+
+/// A coordinate column, given as a 1-based index or a header name
+#[derive(Debug, Clone)]
+enum Column {
+    Index(usize),
+    Name(String),
+}
+
+impl Column {
+    fn parse(column: &str) -> Column {
+        match column.trim().parse::<usize>() {
+            Ok(index) if index > 0 => Column::Index(index - 1),
+            _ => Column::Name(column.trim().to_string()),
+        }
+    }
+
+    fn resolve(&self, headers: Option<&StringRecord>) -> Result<usize, CliError> {
+        match (self, headers) {
+            (Column::Index(index), _) => Ok(*index),
+            (Column::Name(name), Some(headers)) => headers
+                .iter()
+                .position(|header| header == name)
+                .ok_or_else(|| CliError::Usage(format!("there is no column named {}", name))),
+            (Column::Name(name), None) => Err(CliError::Usage(format!(
+                "the column {} can only be named if the input has a header",
+                name
+            ))),
+        }
+    }
+}
+
+struct Options {
+    columns: Vec<Column>,
+    delimiter: u8,
+    header: bool,
+    precision: Option<usize>,
+    on_error: OnError,
+}
+
+/// Add the arguments of the transform command to `app`
+pub fn args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.arg(
+        Arg::with_name("from")
+            .long("from")
+            .short("f")
+            .value_name("CRS")
+            .help("The source CRS, e.g. EPSG:4326")
+            .takes_value(true)
+            .requires("to")
+            .required_unless("pipeline"),
+    )
+    .arg(
+        Arg::with_name("to")
+            .long("to")
+            .short("t")
+            .value_name("CRS")
+            .help("The target CRS")
+            .takes_value(true)
+            .requires("from"),
+    )
+    .arg(
+        Arg::with_name("pipeline")
+            .long("pipeline")
+            .short("p")
+            .value_name("DEFINITION")
+            .help("A PROJ string or pipeline to apply, instead of transforming between CRSs")
+            .takes_value(true)
+            .conflicts_with_all(&["from", "to"]),
+    )
+    .arg(
+        Arg::with_name("columns")
+            .long("columns")
+            .short("c")
+            .value_name("X,Y[,Z]")
+            .help("The coordinate columns, as 1-based indices or header names")
+            .takes_value(true)
+            .default_value("1,2"),
+    )
+    .arg(
+        Arg::with_name("delimiter")
+            .long("delimiter")
+            .short("d")
+            .value_name("CHAR")
+            .help("The field delimiter of the input and output, or \"tab\"")
+            .takes_value(true)
+            .default_value(","),
+    )
+    .arg(
+        Arg::with_name("header")
+            .long("header")
+            .help("The input starts with a header, which is copied to the output"),
+    )
+    .arg(
+        Arg::with_name("precision")
+            .long("precision")
+            .value_name("DIGITS")
+            .help("The number of decimal places of transformed coordinates [default: as many as needed]")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("on-error")
+            .long("on-error")
+            .value_name("ACTION")
+            .help("What to do with records which can't be transformed")
+            .takes_value(true)
+            .possible_values(&["fail", "skip", "keep"])
+            .default_value("fail"),
+    )
+    .arg(
+        Arg::with_name("input")
+            .value_name("FILE")
+            .help("The files to transform, or - for standard input [default: standard input]")
+            .multiple(true),
+    )
+}
+
+fn options(matches: &ArgMatches) -> Result<Options, CliError> {
+    let columns: Vec<Column> = matches
+        .value_of("columns")
+        .unwrap()
+        .split(',')
+        .map(Column::parse)
+        .collect();
+    if columns.len() != 2 && columns.len() != 3 {
+        return Err(CliError::Usage(
+            "--columns must give two or three columns".to_string(),
+        ));
+    }
+    let delimiter = match matches.value_of("delimiter").unwrap() {
+        "tab" | "\\t" => b'\t',
+        delimiter if delimiter.len() == 1 => delimiter.as_bytes()[0],
+        delimiter => {
+            return Err(CliError::Usage(format!(
+                "the delimiter must be a single character, not {}",
+                delimiter
+            )))
+        }
+    };
+    let precision = match matches.value_of("precision") {
+        Some(precision) => Some(
+            precision
+                .parse()
+                .map_err(|_| CliError::Usage(format!("invalid precision {}", precision)))?,
+        ),
+        None => None,
+    };
+    let on_error = match matches.value_of("on-error").unwrap() {
+        "skip" => OnError::Skip,
+        "keep" => OnError::Keep,
+        _ => OnError::Fail,
+    };
+    Ok(Options {
+        columns,
+        delimiter,
+        header: matches.is_present("header"),
+        precision,
+        on_error,
+    })
+}
+
+fn create_proj(matches: &ArgMatches) -> Result<Proj, CliError> {
+    match (
+        matches.value_of("pipeline"),
+        matches.value_of("from"),
+        matches.value_of("to"),
+    ) {
+        (Some(pipeline), _, _) => Proj::new(pipeline)
+            .ok_or_else(|| CliError::Usage(format!("invalid pipeline {}", pipeline))),
+        (None, Some(from), Some(to)) => Proj::new_known_crs(from, to, None)
+            .ok_or_else(|| CliError::Usage(format!("no transformation from {} to {}", from, to))),
+        _ => Err(CliError::Usage(
+            "either --from and --to, or --pipeline, are required".to_string(),
+        )),
+    }
+}
+
+/// Transform the coordinates of the input files, writing the results to standard output
+pub fn run(matches: &ArgMatches) -> Result<(), CliError> {
+    let options = options(matches)?;
+    let proj = create_proj(matches)?;
+    let stdout = io::stdout();
+    let mut writer = WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .flexible(true)
+        .from_writer(stdout.lock());
+    let inputs: Vec<&str> = matches
+        .values_of("input")
+        .map_or_else(|| vec!["-"], |inputs| inputs.collect());
+    for (i, input) in inputs.iter().enumerate() {
+        let reader: Box<dyn Read> = if *input == "-" {
+            Box::new(io::stdin())
+        } else {
+            Box::new(
+                File::open(input)
+                    .map_err(|err| CliError::Usage(format!("couldn't open {}: {}", input, err)))?,
+            )
+        };
+        // only the first header is written
+        transform(&proj, &options, reader, &mut writer, i == 0)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Transform the records of a single input
+fn transform<R: Read, W: Write>(
+    proj: &Proj,
+    options: &Options,
+    reader: R,
+    writer: &mut Writer<W>,
+    write_header: bool,
+) -> Result<(), CliError> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .has_headers(options.header)
+        .flexible(true)
+        .trim(Trim::All)
+        .from_reader(reader);
+    let headers = if options.header {
+        let headers = reader.headers()?.clone();
+        if write_header {
+            writer.write_record(&headers)?;
+        }
+        Some(headers)
+    } else {
+        None
+    };
+    let columns = options
+        .columns
+        .iter()
+        .map(|column| column.resolve(headers.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    for record in reader.records() {
+        batch.push(record?);
+        if batch.len() == BATCH_SIZE {
+            transform_batch(proj, options, &columns, &batch, writer)?;
+            batch.clear();
+        }
+    }
+    transform_batch(proj, options, &columns, &batch, writer)
+}
+
+fn parse_coordinates(record: &StringRecord, columns: &[usize]) -> Result<[f64; 3], String> {
+    let mut point = [0.0; 3];
+    for (ordinate, &column) in point.iter_mut().zip(columns) {
+        let field = record
+            .get(column)
+            .ok_or_else(|| format!("there is no column {}", column + 1))?;
+        *ordinate = field
+            .parse()
+            .map_err(|_| format!("invalid coordinate {:?} in column {}", field, column + 1))?;
+    }
+    Ok(point)
+}
+
+/// Transform a batch of records, and write them according to `options`
+fn transform_batch<W: Write>(
+    proj: &Proj,
+    options: &Options,
+    columns: &[usize],
+    batch: &[StringRecord],
+    writer: &mut Writer<W>,
+) -> Result<(), CliError> {
+    let mut results: Vec<Result<[f64; 3], String>> = batch
+        .iter()
+        .map(|record| parse_coordinates(record, columns))
+        .collect();
+    let mut points: Vec<[f64; 3]> = results.iter().filter_map(|r| r.clone().ok()).collect();
+    if proj.convert_array(&mut points).is_ok() {
+        let mut transformed = points.into_iter();
+        for result in results.iter_mut().filter(|result| result.is_ok()) {
+            *result = Ok(transformed.next().unwrap());
+        }
+    } else {
+        // find the records which can't be transformed
+        for result in results.iter_mut() {
+            if let Ok(point) = result {
+                let mut points = [*point];
+                *result = proj
+                    .convert_array(&mut points)
+                    .map(|points| points[0])
+                    .map_err(|err| err.to_string());
+            }
+        }
+    }
+
+    for (record, result) in batch.iter().zip(results) {
+        let line = record.position().map_or(0, |position| position.line());
+        match result {
+            Ok(point) => {
+                let mut fields: Vec<String> = record.iter().map(str::to_string).collect();
+                for (&column, ordinate) in columns.iter().zip(&point) {
+                    fields[column] = match options.precision {
+                        Some(precision) => format!("{:.*}", precision, ordinate),
+                        None => ordinate.to_string(),
+                    };
+                }
+                writer.write_record(&fields)?;
+            }
+            Err(message) if options.on_error == OnError::Fail => {
+                return Err(CliError::Record { line, message });
+            }
+            Err(message) => {
+                eprintln!("proj-rs: line {}: {}", line, message);
+                if options.on_error == OnError::Keep {
+                    writer.write_record(record)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,340 @@
+use crate::proj::{Area, Proj, ProjBuilder, ProjError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Options for the `PROJ` context of transformers created by a
+/// [`TransformerCache`](struct.TransformerCache.html)
+///
+/// Transformers created with different options are cached separately.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TransformerOptions {
+    /// The directories in which `PROJ` looks for resource files, overriding the defaults
+    pub search_paths: Option<Vec<String>>,
+    /// Whether network access is enabled, for fetching remote grids
+    pub network: bool,
+}
+
+impl TransformerOptions {
+    fn builder(&self) -> Result<ProjBuilder, ProjError> {
+        let mut builder = ProjBuilder::new();
+        if let Some(paths) = &self.search_paths {
+            builder.set_search_paths(paths);
+        }
+        if self.network {
+            builder.enable_network(true)?;
+        }
+        Ok(builder)
+    }
+}
+
+/// Statistics of a [`TransformerCache`](struct.TransformerCache.html)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The number of requests served by a cached transformer
+    pub hits: u64,
+    /// The number of requests which required a new transformer to be created
+    pub misses: u64,
+    /// The number of transformers evicted to make room for new ones
+    pub evictions: u64,
+    /// The number of transformers currently cached
+    pub entries: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    from: String,
+    to: String,
+    // the bounding box, as bits, so that it can be hashed
+    area: Option<[u64; 4]>,
+    options: TransformerOptions,
+}
+
+struct Entry {
+    proj: Arc<Mutex<Proj>>,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<CacheKey, Entry>,
+    clock: u64,
+    stats: CacheStats,
+}
+
+/// A thread-safe cache of transformers between coordinate reference systems
+///
+/// Creating a transformer with [`Proj::new_known_crs`](struct.Proj.html#method.new_known_crs)
+/// involves database queries and the selection of coordinate operations, which is expensive.
+/// The cache keeps a transformer for each combination of source and target CRS, area of use and
+/// [`TransformerOptions`](struct.TransformerOptions.html), and hands out shared handles to it.
+/// As a `PROJ` object can't be used concurrently, each transformer is behind a `Mutex`, which must be
+/// locked to use it; the handles can be moved to, and used on, any thread.
+///
+/// When the cache is full, the least recently used transformer is evicted.
+///
+///```rust
+/// # use assert_approx_eq::assert_approx_eq;
+/// extern crate proj;
+/// use proj::TransformerCache;
+///
+/// extern crate geo_types;
+/// use geo_types::Point;
+///
+/// let cache = TransformerCache::new(16);
+/// let ft_to_m = cache.get("EPSG:2230", "EPSG:26946", None).unwrap();
+/// let result = ft_to_m
+///     .lock()
+///     .unwrap()
+///     .convert(Point::new(4760096.421921f64, 3744293.729449f64))
+///     .unwrap();
+/// assert_approx_eq!(result.x(), 1450880.29, 1e-2);
+/// // the second request is served from the cache
+/// let _ = cache.get("EPSG:2230", "EPSG:26946", None).unwrap();
+/// assert_eq!(cache.stats().hits, 1);
+/// ```
+pub struct TransformerCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+impl TransformerCache {
+    /// Create a cache holding at most `capacity` transformers
+    ///
+    /// A `capacity` of 0 is treated as 1.
+    pub fn new(capacity: usize) -> Self {
+        TransformerCache {
+            capacity: capacity.max(1),
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// The maximum number of transformers held
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get a transformer between two CRSs, using the default `TransformerOptions`
+    ///
+    /// See [`Proj::new_known_crs`](struct.Proj.html#method.new_known_crs) for the accepted
+    /// CRS definitions and the coordinate order of the transformer.
+    pub fn get(
+        &self,
+        from: &str,
+        to: &str,
+        area: Option<Area>,
+    ) -> Result<Arc<Mutex<Proj>>, ProjError> {
+        self.get_with_options(from, to, area, &TransformerOptions::default())
+    }
+
+    /// Get a transformer between two CRSs, whose context is configured using `options`
+    pub fn get_with_options(
+        &self,
+        from: &str,
+        to: &str,
+        area: Option<Area>,
+        options: &TransformerOptions,
+    ) -> Result<Arc<Mutex<Proj>>, ProjError> {
+        let key = CacheKey {
+            from: from.to_string(),
+            to: to.to_string(),
+            area: area.map(|area| {
+                [
+                    area.west().to_bits(),
+                    area.south().to_bits(),
+                    area.east().to_bits(),
+                    area.north().to_bits(),
+                ]
+            }),
+            options: options.clone(),
+        };
+        {
+            let mut state = self.state.lock().unwrap();
+            state.clock += 1;
+            let clock = state.clock;
+            if let Some(entry) = state.entries.get_mut(&key) {
+                entry.last_used = clock;
+                let proj = Arc::clone(&entry.proj);
+                state.stats.hits += 1;
+                return Ok(proj);
+            }
+            state.stats.misses += 1;
+        }
+        // create the transformer without holding the lock, as this is the expensive part
+        let proj = options
+            .builder()?
+            .proj_known_crs(from, to, area)
+            .ok_or_else(|| ProjError::Crs(format!("no transformation from {} to {}", from, to)))?;
+        let proj = Arc::new(Mutex::new(proj));
+        let mut state = self.state.lock().unwrap();
+        // another thread may have created the same transformer in the meantime
+        if let Some(entry) = state.entries.get(&key) {
+            return Ok(Arc::clone(&entry.proj));
+        }
+        if state.entries.len() >= self.capacity {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+                state.stats.evictions += 1;
+            }
+        }
+        let last_used = state.clock;
+        state.entries.insert(
+            key,
+            Entry {
+                proj: Arc::clone(&proj),
+                last_used,
+            },
+        );
+        Ok(proj)
+    }
+
+    /// The cache's statistics
+    pub fn stats(&self) -> CacheStats {
+        let state = self.state.lock().unwrap();
+        CacheStats {
+            entries: state.entries.len(),
+            ..state.stats
+        }
+    }
+
+    /// The number of transformers currently cached
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    /// Whether no transformers are cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all cached transformers, keeping the statistics
+    pub fn clear(&self) {
+        self.state.lock().unwrap().entries.clear();
+    }
+}
+
+impl Default for TransformerCache {
+    /// Create a cache holding at most 64 transformers
+    fn default() -> Self {
+        TransformerCache::new(64)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{TransformerCache, TransformerOptions};
+    use crate::proj::Area;
+    use geo_types::Point;
+    use std::sync::Arc;
+    use std::thread;
+
+    fn assert_almost_eq(a: f64, b: f64) {
+        let f: f64 = a / b;
+        assert!(f < 1.00001);
+        assert!(f > 0.99999);
+    }
+
+    #[test]
+    fn test_hits_and_misses() {
+        let cache = TransformerCache::new(4);
+        for _ in 0..3 {
+            let proj = cache.get("EPSG:2230", "EPSG:26946", None).unwrap();
+            let result = proj
+                .lock()
+                .unwrap()
+                .convert(Point::new(4760096.421921, 3744293.729449))
+                .unwrap();
+            assert_almost_eq(result.x(), 1450880.29);
+            assert_almost_eq(result.y(), 1141263.01);
+        }
+        // a different area of use is a different transformer
+        let area = Area::new(-117.5, 32.5, -116.5, 33.5);
+        cache.get("EPSG:2230", "EPSG:26946", Some(area)).unwrap();
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.evictions, 0);
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let cache = TransformerCache::new(2);
+        cache.get("EPSG:4326", "EPSG:3857", None).unwrap();
+        cache.get("EPSG:4326", "EPSG:32633", None).unwrap();
+        // use the first, so the second is the least recently used
+        cache.get("EPSG:4326", "EPSG:3857", None).unwrap();
+        cache.get("EPSG:4326", "EPSG:27700", None).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.stats().evictions, 1);
+        cache.get("EPSG:4326", "EPSG:3857", None).unwrap();
+        assert_eq!(cache.stats().hits, 2);
+        cache.get("EPSG:4326", "EPSG:32633", None).unwrap();
+        assert_eq!(cache.stats().misses, 4);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_multiple_operations() {
+        // PROJ chooses between several operations from WGS84 to the British National Grid, and
+        // can't clone such transformers, so they must be shared
+        let cache = TransformerCache::default();
+        let first = cache.get("EPSG:4326", "EPSG:27700", None).unwrap();
+        let second = cache.get("EPSG:4326", "EPSG:27700", None).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        let point = Point::new(-0.1275, 51.50722);
+        let result = second.lock().unwrap().convert(point).unwrap();
+        assert!(result.x() > 529000.0 && result.x() < 531000.0);
+        assert!(result.y() > 179000.0 && result.y() < 181000.0);
+        assert_eq!(cache.stats().hits, 1);
+    }
+
+    #[test]
+    fn test_invalid_crs() {
+        let cache = TransformerCache::default();
+        assert!(cache.get("EPSG:4326", "EPSG:nonsense", None).is_err());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_options() {
+        let cache = TransformerCache::default();
+        let options = TransformerOptions {
+            search_paths: Some(vec!["/nonexistent".to_string()]),
+            network: false,
+        };
+        // without proj.db, known CRSs can't be looked up
+        assert!(cache
+            .get_with_options("EPSG:4326", "EPSG:3857", None, &options)
+            .is_err());
+        cache.get("EPSG:4326", "EPSG:3857", None).unwrap();
+        assert_eq!(cache.stats().misses, 2);
+    }
+
+    #[test]
+    fn test_threads() {
+        let cache = Arc::new(TransformerCache::default());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let cache = Arc::clone(&cache);
+                thread::spawn(move || {
+                    let proj = cache.get("EPSG:2230", "EPSG:26946", None).unwrap();
+                    let proj = proj.lock().unwrap();
+                    proj.convert(Point::new(4760096.421921, 3744293.729449))
+                        .unwrap()
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_almost_eq(handle.join().unwrap().x(), 1450880.29);
+        }
+        assert_eq!(cache.len(), 1);
+        let stats = cache.stats();
+        assert_eq!(stats.hits + stats.misses, 4);
+    }
+}
@@ -0,0 +1,444 @@
+use crate::crs::{CrsType, Ellipsoid};
+use crate::proj::{_string, context_error, Area, ProjError};
+use libc::{c_char, c_int};
+use proj_sys::{
+    proj_context_create, proj_context_destroy, proj_crs_info_list_destroy, proj_dmstor,
+    proj_get_crs_info_list_from_database, proj_get_crs_list_parameters_create,
+    proj_get_crs_list_parameters_destroy, proj_list_angular_units, proj_list_ellps,
+    proj_list_operations, proj_list_prime_meridians, proj_list_units, PJ_ELLPS, PJ_OPERATIONS,
+    PJ_PRIME_MERIDIANS, PJ_UNITS,
+};
+use std::ffi::CString;
+use std::ptr;
+use std::slice;
+
+/// An operation (e.g. a projection) built into `PROJ`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationInfo {
+    /// The operation's identifier, as used in `+proj=<id>`
+    pub id: String,
+    /// A description of the operation: its name, followed by its characteristics and parameters
+    pub description: String,
+}
+
+impl OperationInfo {
+    /// The operation's name, i.e. the first line of its description
+    pub fn name(&self) -> &str {
+        self.description.lines().next().unwrap_or("").trim()
+    }
+}
+
+/// An ellipsoid built into `PROJ`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EllipsoidInfo {
+    /// The ellipsoid's identifier, as used in `+ellps=<id>`
+    pub id: String,
+    /// The ellipsoid's name
+    pub name: String,
+    /// The semi-major axis as a `PROJ` parameter, e.g. `a=6378137.0`
+    pub major: String,
+    /// The ellipse parameter as a `PROJ` parameter, e.g. `rf=298.257223563`
+    pub ell: String,
+}
+
+impl EllipsoidInfo {
+    /// Convert to an [`Ellipsoid`](struct.Ellipsoid.html)
+    ///
+    /// Returns `None` if the parameters can't be interpreted.
+    pub fn to_ellipsoid(&self) -> Option<Ellipsoid> {
+        let semi_major: f64 = self.major.strip_prefix("a=")?.parse().ok()?;
+        let mut ell = self.ell.splitn(2, '=');
+        let (key, value) = (ell.next()?, ell.next()?.parse::<f64>().ok()?);
+        let flattening = match key {
+            "rf" if value != 0.0 => 1.0 / value,
+            "rf" => 0.0,
+            "f" => value,
+            "b" => (semi_major - value) / semi_major,
+            "es" => 1.0 - (1.0 - value).sqrt(),
+            _ => return None,
+        };
+        let inv_flattening = if flattening == 0.0 {
+            0.0
+        } else {
+            1.0 / flattening
+        };
+        Some(Ellipsoid::new(&self.name, semi_major, inv_flattening))
+    }
+}
+
+/// The category of a unit of measure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnitCategory {
+    /// Units of length, with conversion factors to metres
+    Linear,
+    /// Units of angle, with conversion factors to radians
+    Angular,
+}
+
+/// A unit of measure built into `PROJ`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitInfo {
+    /// The unit's identifier, as used in `+units=<id>`
+    pub id: String,
+    /// The unit's name
+    pub name: String,
+    /// The conversion factor to the base unit as defined by `PROJ`, which may be a fraction (e.g. `1/1000`)
+    pub definition: String,
+    /// The conversion factor to the base unit of `category`
+    pub factor: f64,
+    /// Whether the unit is linear or angular
+    pub category: UnitCategory,
+}
+
+/// A prime meridian built into `PROJ`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrimeMeridianInfo {
+    /// The prime meridian's identifier, as used in `+pm=<id>`
+    pub id: String,
+    /// The longitude from Greenwich as defined by `PROJ`, e.g. `2d20'14.025"E`
+    pub definition: String,
+    /// The longitude from Greenwich, in degrees
+    pub longitude: f64,
+}
+
+/// A CRS in the `PROJ` database
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrsInfo {
+    /// The authority which defined the CRS, e.g. `EPSG`
+    pub auth_name: String,
+    /// The code of the CRS within its authority, e.g. `32633`
+    pub code: String,
+    /// The name of the CRS, e.g. `WGS 84 / UTM zone 33N`
+    pub name: String,
+    /// The type of the CRS, if it is known
+    pub crs_type: Option<CrsType>,
+    /// Whether the CRS is deprecated, i.e. superseded by another CRS of its authority
+    pub deprecated: bool,
+    /// The bounding box of the area of use of the CRS in degrees, if it is known
+    pub area: Option<Area>,
+    /// A description of the area of use of the CRS
+    pub area_name: String,
+    /// The name of the projection method of a projected CRS, e.g. `Transverse Mercator`
+    pub projection_method_name: String,
+}
+
+impl CrsInfo {
+    /// The identifier of the CRS, as accepted by [`Proj::new()`](struct.Proj.html#method.new),
+    /// e.g. `EPSG:32633`
+    pub fn id(&self) -> String {
+        format!("{}:{}", self.auth_name, self.code)
+    }
+
+    /// Whether every word of `query` occurs in the identifier or name of the CRS, ignoring case
+    ///
+    ///```rust
+    /// extern crate proj;
+    ///
+    /// let utm: Vec<_> = proj::list_crs(Some("EPSG"), false)
+    ///     .unwrap()
+    ///     .into_iter()
+    ///     .filter(|crs| crs.matches("UTM 33"))
+    ///     .collect();
+    /// assert!(utm.iter().any(|crs| crs.id() == "EPSG:32633"));
+    /// ```
+    pub fn matches(&self, query: &str) -> bool {
+        let text = format!("{} {}", self.id(), self.name).to_lowercase();
+        query
+            .split_whitespace()
+            .all(|word| text.contains(&word.to_lowercase()))
+    }
+}
+
+/// Iterate over a static `PROJ` list, which is terminated by an entry with a null id
+fn static_list<T: 'static>(
+    list: *const T,
+    id: fn(&T) -> *const c_char,
+) -> impl Iterator<Item = &'static T> {
+    (0..)
+        .take_while(move |&i| !list.is_null() && !id(unsafe { &*list.add(i) }).is_null())
+        .map(move |i| unsafe { &*list.add(i) })
+}
+
+fn optional_string(raw_ptr: *const c_char) -> String {
+    if raw_ptr.is_null() {
+        String::new()
+    } else {
+        _string(raw_ptr)
+    }
+}
+
+/// The operations (projections, conversions and transformations) built into `PROJ`
+///
+///```rust
+/// extern crate proj;
+///
+/// let tmerc = proj::list_operations().find(|op| op.id == "tmerc").unwrap();
+/// assert_eq!(tmerc.name(), "Transverse Mercator");
+/// ```
+///
+/// # Safety
+/// This method contains unsafe code.
+pub fn list_operations() -> impl Iterator<Item = OperationInfo> {
+    let list: *const PJ_OPERATIONS = unsafe { proj_list_operations() };
+    static_list(list, |op| op.id).map(|op| OperationInfo {
+        id: _string(op.id),
+        description: if op.descr.is_null() {
+            String::new()
+        } else {
+            optional_string(unsafe { *op.descr })
+        },
+    })
+}
+
+/// The ellipsoids built into `PROJ`
+///
+/// # Safety
+/// This method contains unsafe code.
+pub fn list_ellipsoids() -> impl Iterator<Item = EllipsoidInfo> {
+    let list: *const PJ_ELLPS = unsafe { proj_list_ellps() };
+    static_list(list, |ellps| ellps.id).map(|ellps| EllipsoidInfo {
+        id: _string(ellps.id),
+        name: optional_string(ellps.name),
+        major: optional_string(ellps.major),
+        ell: optional_string(ellps.ell),
+    })
+}
+
+/// The linear and angular units built into `PROJ`
+///
+/// **Note:** querying the units in the `PROJ` database (`proj_get_units_from_database`)
+/// requires PROJ 7.1, and is not supported by the version of PROJ these bindings target.
+///
+///```rust
+/// extern crate proj;
+/// use proj::UnitCategory;
+///
+/// let us_ft = proj::list_units().find(|unit| unit.id == "us-ft").unwrap();
+/// assert_eq!(us_ft.category, UnitCategory::Linear);
+/// assert!((us_ft.factor - 0.304800609601219).abs() < 1e-12);
+/// ```
+///
+/// # Safety
+/// This method contains unsafe code.
+pub fn list_units() -> impl Iterator<Item = UnitInfo> {
+    let linear: *const PJ_UNITS = unsafe { proj_list_units() };
+    let angular: *const PJ_UNITS = unsafe { proj_list_angular_units() };
+    let unit = |category| {
+        move |unit: &PJ_UNITS| UnitInfo {
+            id: _string(unit.id),
+            name: optional_string(unit.name),
+            definition: optional_string(unit.to_meter),
+            factor: unit.factor,
+            category,
+        }
+    };
+    static_list(linear, |unit| unit.id)
+        .map(unit(UnitCategory::Linear))
+        .chain(static_list(angular, |unit| unit.id).map(unit(UnitCategory::Angular)))
+}
+
+/// The prime meridians built into `PROJ`
+///
+/// # Safety
+/// This method contains unsafe code.
+pub fn list_prime_meridians() -> impl Iterator<Item = PrimeMeridianInfo> {
+    let list: *const PJ_PRIME_MERIDIANS = unsafe { proj_list_prime_meridians() };
+    static_list(list, |pm| pm.id).map(|pm| {
+        let definition = optional_string(pm.defn);
+        let c_definition = CString::new(definition.as_bytes()).unwrap();
+        let longitude = unsafe { proj_dmstor(c_definition.as_ptr(), ptr::null_mut()) };
+        PrimeMeridianInfo {
+            id: _string(pm.id),
+            definition,
+            longitude: longitude.to_degrees(),
+        }
+    })
+}
+
+/// The CRS in the `PROJ` database, optionally restricted to those of a single authority
+///
+/// Deprecated CRS are only included if `allow_deprecated` is `true`.
+/// Use [`CrsInfo::matches()`](struct.CrsInfo.html#method.matches) to search the list.
+///
+///```rust
+/// extern crate proj;
+/// use proj::CrsType;
+///
+/// let crs = proj::list_crs(Some("EPSG"), false).unwrap();
+/// let wgs84 = crs.iter().find(|crs| crs.code == "4326").unwrap();
+/// assert_eq!(wgs84.name, "WGS 84");
+/// assert_eq!(wgs84.crs_type, Some(CrsType::Geographic2D));
+/// ```
+///
+/// # Safety
+/// This method contains unsafe code.
+pub fn list_crs(
+    authority: Option<&str>,
+    allow_deprecated: bool,
+) -> Result<Vec<CrsInfo>, ProjError> {
+    let authority = authority
+        .map(|authority| {
+            CString::new(authority)
+                .map_err(|_| ProjError::Database(format!("invalid authority name {:?}", authority)))
+        })
+        .transpose()?;
+    unsafe {
+        let ctx = proj_context_create();
+        let params = proj_get_crs_list_parameters_create();
+        (*params).allow_deprecated = allow_deprecated as c_int;
+        let mut count = 0;
+        let list = proj_get_crs_info_list_from_database(
+            ctx,
+            authority
+                .as_ref()
+                .map_or(ptr::null(), |authority| authority.as_ptr()),
+            params,
+            &mut count,
+        );
+        proj_get_crs_list_parameters_destroy(params);
+        if list.is_null() {
+            let err = context_error(ctx);
+            proj_context_destroy(ctx);
+            return Err(ProjError::Database(err));
+        }
+        let crs = slice::from_raw_parts(list, count as usize)
+            .iter()
+            .map(|&info| {
+                let info = &*info;
+                CrsInfo {
+                    auth_name: optional_string(info.auth_name),
+                    code: optional_string(info.code),
+                    name: optional_string(info.name),
+                    crs_type: CrsType::from_raw(info.type_),
+                    deprecated: info.deprecated != 0,
+                    area: if info.bbox_valid != 0 {
+                        Some(Area::new(
+                            info.west_lon_degree,
+                            info.south_lat_degree,
+                            info.east_lon_degree,
+                            info.north_lat_degree,
+                        ))
+                    } else {
+                        None
+                    },
+                    area_name: optional_string(info.area_name),
+                    projection_method_name: optional_string(info.projection_method_name),
+                }
+            })
+            .collect();
+        proj_crs_info_list_destroy(list);
+        proj_context_destroy(ctx);
+        Ok(crs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        list_crs, list_ellipsoids, list_operations, list_prime_meridians, list_units, CrsInfo,
+        EllipsoidInfo, UnitCategory,
+    };
+    use crate::crs::CrsType;
+
+    fn assert_almost_eq(a: f64, b: f64) {
+        let f: f64 = a / b;
+        assert!(f < 1.00001);
+        assert!(f > 0.99999);
+    }
+
+    #[test]
+    fn test_list_operations() {
+        let ops: Vec<_> = list_operations().collect();
+        assert!(ops.len() > 100);
+        let merc = ops.iter().find(|op| op.id == "merc").unwrap();
+        assert_eq!(merc.name(), "Mercator");
+        assert!(ops.iter().any(|op| op.id == "pipeline"));
+    }
+
+    #[test]
+    fn test_list_ellipsoids() {
+        let grs80 = list_ellipsoids()
+            .find(|ellps| ellps.id == "GRS80")
+            .unwrap()
+            .to_ellipsoid()
+            .unwrap();
+        assert_eq!(grs80.semi_major, 6378137.0);
+        assert_almost_eq(grs80.inv_flattening, 298.257222101);
+        // every built-in ellipsoid can be interpreted
+        assert!(list_ellipsoids().all(|ellps| ellps.to_ellipsoid().is_some()));
+    }
+
+    #[test]
+    fn test_ellipsoid_from_semi_minor() {
+        let sphere = EllipsoidInfo {
+            id: "sphere".to_string(),
+            name: "Normal Sphere (r=6370997)".to_string(),
+            major: "a=6370997.0".to_string(),
+            ell: "b=6370997.0".to_string(),
+        };
+        assert_eq!(sphere.to_ellipsoid().unwrap().inv_flattening, 0.0);
+    }
+
+    #[test]
+    fn test_list_units() {
+        let km = list_units().find(|unit| unit.id == "km").unwrap();
+        assert_eq!(km.category, UnitCategory::Linear);
+        assert_eq!(km.factor, 1000.0);
+        let grad = list_units().find(|unit| unit.id == "grad").unwrap();
+        assert_eq!(grad.category, UnitCategory::Angular);
+        assert_almost_eq(grad.factor, std::f64::consts::PI / 200.0);
+    }
+
+    #[test]
+    fn test_list_prime_meridians() {
+        let paris = list_prime_meridians().find(|pm| pm.id == "paris").unwrap();
+        assert_almost_eq(paris.longitude, 2.33722917);
+        let greenwich = list_prime_meridians()
+            .find(|pm| pm.id == "greenwich")
+            .unwrap();
+        assert_eq!(greenwich.longitude, 0.0);
+    }
+
+    #[test]
+    fn test_list_crs() {
+        let epsg = list_crs(Some("EPSG"), false).unwrap();
+        assert!(epsg.len() > 1000);
+        assert!(epsg
+            .iter()
+            .all(|crs| crs.auth_name == "EPSG" && !crs.deprecated));
+        let utm = epsg.iter().find(|crs| crs.id() == "EPSG:32633").unwrap();
+        assert_eq!(utm.name, "WGS 84 / UTM zone 33N");
+        assert_eq!(utm.crs_type, Some(CrsType::Projected));
+        assert_eq!(utm.projection_method_name, "Transverse Mercator");
+        let area = utm.area.unwrap();
+        assert_eq!(area.west(), 12.0);
+        assert_eq!(area.east(), 18.0);
+        // deprecated CRS are only listed if allowed
+        let all = list_crs(Some("EPSG"), true).unwrap();
+        assert!(all.iter().any(|crs| crs.deprecated));
+    }
+
+    #[test]
+    fn test_list_crs_invalid_authority() {
+        assert!(list_crs(Some("EP\0SG"), false).is_err());
+    }
+
+    #[test]
+    fn test_crs_matches() {
+        let crs = CrsInfo {
+            auth_name: "EPSG".to_string(),
+            code: "32633".to_string(),
+            name: "WGS 84 / UTM zone 33N".to_string(),
+            crs_type: Some(CrsType::Projected),
+            deprecated: false,
+            area: None,
+            area_name: String::new(),
+            projection_method_name: "Transverse Mercator".to_string(),
+        };
+        assert!(crs.matches("UTM 33"));
+        assert!(crs.matches("utm ZONE 33n"));
+        assert!(crs.matches("epsg:32633"));
+        assert!(crs.matches(""));
+        assert!(!crs.matches("UTM 34"));
+    }
+}
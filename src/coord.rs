@@ -0,0 +1,124 @@
+use geo_types::{Coordinate, Point};
+use num_traits::Float;
+
+/// A coordinate which can be transformed in place by the batch methods of `Proj`,
+/// such as [`convert_array`](struct.Proj.html#method.convert_array) and
+/// [`project_array`](struct.Proj.html#method.project_array)
+///
+/// This is implemented for `Point`, `Coordinate`, and 2D and 3D tuples and arrays,
+/// so slices of any of these (including the coordinates of a `LineString`) can be
+/// transformed without first being converted to `Point`s.
+/// The `z` component of 3D coordinates is transformed along with `x` and `y`.
+///
+///```rust
+/// # use assert_approx_eq::assert_approx_eq;
+/// extern crate proj;
+/// use proj::Proj;
+///
+/// let ft_to_m = Proj::new_known_crs("EPSG:2230", "EPSG:26946", None).unwrap();
+/// let mut v = vec![(4760096.421921f64, 3744293.729449f64), (4760197.421921, 3744394.729449)];
+/// ft_to_m.convert_array(&mut v).unwrap();
+/// assert_approx_eq!(v[0].0, 1450880.29, 1e-2);
+/// assert_approx_eq!(v[0].1, 1141263.01, 1e-2);
+/// ```
+pub trait Coord<T: Float> {
+    /// The x component, e.g. the easting or longitude
+    fn x(&self) -> T;
+    /// The y component, e.g. the northing or latitude
+    fn y(&self) -> T;
+    /// The z component, if the coordinate has one
+    fn z(&self) -> Option<T> {
+        None
+    }
+    /// Set the x and y components, leaving any z component unchanged
+    fn set_xy(&mut self, x: T, y: T);
+    /// Set the z component, if the coordinate has one
+    fn set_z(&mut self, _z: T) {}
+}
+
+impl<T: Float> Coord<T> for Point<T> {
+    fn x(&self) -> T {
+        self.0.x
+    }
+    fn y(&self) -> T {
+        self.0.y
+    }
+    fn set_xy(&mut self, x: T, y: T) {
+        self.0 = Coordinate { x, y };
+    }
+}
+
+impl<T: Float> Coord<T> for Coordinate<T> {
+    fn x(&self) -> T {
+        self.x
+    }
+    fn y(&self) -> T {
+        self.y
+    }
+    fn set_xy(&mut self, x: T, y: T) {
+        self.x = x;
+        self.y = y;
+    }
+}
+
+impl<T: Float> Coord<T> for (T, T) {
+    fn x(&self) -> T {
+        self.0
+    }
+    fn y(&self) -> T {
+        self.1
+    }
+    fn set_xy(&mut self, x: T, y: T) {
+        *self = (x, y);
+    }
+}
+
+impl<T: Float> Coord<T> for [T; 2] {
+    fn x(&self) -> T {
+        self[0]
+    }
+    fn y(&self) -> T {
+        self[1]
+    }
+    fn set_xy(&mut self, x: T, y: T) {
+        *self = [x, y];
+    }
+}
+
+impl<T: Float> Coord<T> for (T, T, T) {
+    fn x(&self) -> T {
+        self.0
+    }
+    fn y(&self) -> T {
+        self.1
+    }
+    fn z(&self) -> Option<T> {
+        Some(self.2)
+    }
+    fn set_xy(&mut self, x: T, y: T) {
+        self.0 = x;
+        self.1 = y;
+    }
+    fn set_z(&mut self, z: T) {
+        self.2 = z;
+    }
+}
+
+impl<T: Float> Coord<T> for [T; 3] {
+    fn x(&self) -> T {
+        self[0]
+    }
+    fn y(&self) -> T {
+        self[1]
+    }
+    fn z(&self) -> Option<T> {
+        Some(self[2])
+    }
+    fn set_xy(&mut self, x: T, y: T) {
+        self[0] = x;
+        self[1] = y;
+    }
+    fn set_z(&mut self, z: T) {
+        self[2] = z;
+    }
+}
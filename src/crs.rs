@@ -0,0 +1,1409 @@
+use crate::proj::{_string, Area, Proj};
+use libc::{c_char, c_int};
+use proj_sys::{
+    proj_as_projjson, proj_as_wkt, proj_clone, proj_context_create, proj_create_cartesian_2D_cs,
+    proj_create_compound_crs, proj_create_conversion_lambert_azimuthal_equal_area,
+    proj_create_conversion_lambert_conic_conformal_1sp,
+    proj_create_conversion_lambert_conic_conformal_2sp,
+    proj_create_conversion_oblique_stereographic,
+    proj_create_conversion_pole_rotation_grib_convention,
+    proj_create_conversion_transverse_mercator, proj_create_conversion_utm,
+    proj_create_derived_geographic_crs, proj_create_ellipsoidal_2D_cs, proj_create_geographic_crs,
+    proj_create_projected_crs, proj_crs_get_coordinate_system, proj_crs_get_datum,
+    proj_crs_get_horizontal_datum, proj_crs_get_sub_crs, proj_cs_get_axis_count,
+    proj_cs_get_axis_info, proj_destroy, proj_ellipsoid_get_parameters, proj_get_area_of_use,
+    proj_get_ellipsoid, proj_get_id_auth_name, proj_get_id_code, proj_get_name,
+    proj_get_prime_meridian, proj_get_source_crs, proj_get_target_crs, proj_get_type,
+    proj_identify, proj_int_list_destroy, proj_is_crs, proj_is_deprecated, proj_list_destroy,
+    proj_list_get, proj_list_get_count, proj_prime_meridian_get_parameters, PJconsts,
+    PJ_CART2D_TYPE_PJ_CART2D_EASTING_NORTHING, PJ_CONTEXT,
+    PJ_ELLIPSOIDAL_CS_2D_TYPE_PJ_ELLPS2D_LATITUDE_LONGITUDE, PJ_TYPE, PJ_TYPE_PJ_TYPE_BOUND_CRS,
+    PJ_TYPE_PJ_TYPE_COMPOUND_CRS, PJ_TYPE_PJ_TYPE_CRS, PJ_TYPE_PJ_TYPE_ENGINEERING_CRS,
+    PJ_TYPE_PJ_TYPE_GEOCENTRIC_CRS, PJ_TYPE_PJ_TYPE_GEODETIC_CRS,
+    PJ_TYPE_PJ_TYPE_GEOGRAPHIC_2D_CRS, PJ_TYPE_PJ_TYPE_GEOGRAPHIC_3D_CRS,
+    PJ_TYPE_PJ_TYPE_GEOGRAPHIC_CRS, PJ_TYPE_PJ_TYPE_OTHER_CRS, PJ_TYPE_PJ_TYPE_PROJECTED_CRS,
+    PJ_TYPE_PJ_TYPE_TEMPORAL_CRS, PJ_TYPE_PJ_TYPE_VERTICAL_CRS, PJ_WKT_TYPE_PJ_WKT2_2019,
+};
+use std::ffi::CString;
+use std::fmt;
+use std::ptr;
+
+/// A unit of measure, defined by its name and its conversion factor to the SI unit
+/// (metres for linear units, radians for angular units)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    /// The name of the unit, e.g. `metre`
+    pub name: String,
+    /// The conversion factor to metres or radians
+    pub factor: f64,
+}
+
+impl Unit {
+    /// Create a new unit of measure
+    pub fn new(name: &str, factor: f64) -> Self {
+        Unit {
+            name: name.to_string(),
+            factor,
+        }
+    }
+
+    /// The metre
+    pub fn metre() -> Self {
+        Unit::new("metre", 1.0)
+    }
+
+    /// The international foot
+    pub fn foot() -> Self {
+        Unit::new("foot", 0.3048)
+    }
+
+    /// The US survey foot
+    pub fn us_survey_foot() -> Self {
+        Unit::new("US survey foot", 0.304_800_609_601_219_2)
+    }
+
+    /// The degree
+    pub fn degree() -> Self {
+        Unit::new("degree", 0.017_453_292_519_943_295)
+    }
+
+    /// The radian
+    pub fn radian() -> Self {
+        Unit::new("radian", 1.0)
+    }
+}
+
+/// An ellipsoid, defined by its semi-major axis (in metres) and its inverse flattening
+///
+/// A sphere has an inverse flattening of `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ellipsoid {
+    /// The name of the ellipsoid, e.g. `WGS 84`
+    pub name: String,
+    /// The semi-major axis, in metres
+    pub semi_major: f64,
+    /// The inverse flattening, or `0` for a sphere
+    pub inv_flattening: f64,
+}
+
+impl Ellipsoid {
+    /// Create a new ellipsoid
+    pub fn new(name: &str, semi_major: f64, inv_flattening: f64) -> Self {
+        Ellipsoid {
+            name: name.to_string(),
+            semi_major,
+            inv_flattening,
+        }
+    }
+
+    /// The WGS 84 ellipsoid
+    pub fn wgs84() -> Self {
+        Ellipsoid::new("WGS 84", 6_378_137.0, 298.257_223_563)
+    }
+
+    /// The GRS 1980 ellipsoid
+    pub fn grs80() -> Self {
+        Ellipsoid::new("GRS 1980", 6_378_137.0, 298.257_222_101)
+    }
+
+    /// The flattening of the ellipsoid
+    pub fn flattening(&self) -> f64 {
+        if self.inv_flattening == 0.0 {
+            0.0
+        } else {
+            1.0 / self.inv_flattening
+        }
+    }
+
+    /// The semi-minor axis of the ellipsoid, in metres
+    pub fn semi_minor(&self) -> f64 {
+        self.semi_major * (1.0 - self.flattening())
+    }
+
+    /// The ellipsoid as `PROJ` string parameters
+    pub(crate) fn proj_params(&self) -> String {
+        format!("+a={} +b={}", self.semi_major, self.semi_minor())
+    }
+}
+
+/// A prime meridian, defined by its longitude from Greenwich
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrimeMeridian {
+    /// The name of the prime meridian, e.g. `Greenwich`
+    pub name: String,
+    /// The longitude from Greenwich, in `unit`
+    pub longitude: f64,
+    /// The angular unit of `longitude`
+    pub unit: Unit,
+}
+
+impl PrimeMeridian {
+    /// The longitude from Greenwich, in degrees
+    pub fn longitude_degrees(&self) -> f64 {
+        (self.longitude * self.unit.factor).to_degrees()
+    }
+}
+
+/// A geodetic datum
+#[derive(Debug, Clone, PartialEq)]
+pub struct Datum {
+    /// The name of the datum, e.g. `World Geodetic System 1984`
+    pub name: String,
+    /// The ellipsoid of the datum, if it is a geodetic datum
+    pub ellipsoid: Option<Ellipsoid>,
+    /// The prime meridian of the datum, if it is a geodetic datum
+    pub prime_meridian: Option<PrimeMeridian>,
+}
+
+/// The type of a coordinate reference system
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrsType {
+    Geodetic,
+    Geocentric,
+    Geographic2D,
+    Geographic3D,
+    Vertical,
+    Projected,
+    Compound,
+    Temporal,
+    Engineering,
+    /// A CRS with a transformation to a hub CRS (usually WGS 84), e.g. from `+towgs84`
+    Bound,
+    Other,
+}
+
+impl CrsType {
+    /// Convert a `PROJ` object type, returning `None` if it isn't the type of a CRS
+    pub(crate) fn from_raw(pj_type: PJ_TYPE) -> Option<CrsType> {
+        match pj_type {
+            PJ_TYPE_PJ_TYPE_GEODETIC_CRS => Some(CrsType::Geodetic),
+            PJ_TYPE_PJ_TYPE_GEOCENTRIC_CRS => Some(CrsType::Geocentric),
+            // a geographic CRS of unknown dimension is 2D
+            PJ_TYPE_PJ_TYPE_GEOGRAPHIC_CRS | PJ_TYPE_PJ_TYPE_GEOGRAPHIC_2D_CRS => {
+                Some(CrsType::Geographic2D)
+            }
+            PJ_TYPE_PJ_TYPE_GEOGRAPHIC_3D_CRS => Some(CrsType::Geographic3D),
+            PJ_TYPE_PJ_TYPE_VERTICAL_CRS => Some(CrsType::Vertical),
+            PJ_TYPE_PJ_TYPE_PROJECTED_CRS => Some(CrsType::Projected),
+            PJ_TYPE_PJ_TYPE_COMPOUND_CRS => Some(CrsType::Compound),
+            PJ_TYPE_PJ_TYPE_TEMPORAL_CRS => Some(CrsType::Temporal),
+            PJ_TYPE_PJ_TYPE_ENGINEERING_CRS => Some(CrsType::Engineering),
+            PJ_TYPE_PJ_TYPE_BOUND_CRS => Some(CrsType::Bound),
+            PJ_TYPE_PJ_TYPE_CRS | PJ_TYPE_PJ_TYPE_OTHER_CRS => Some(CrsType::Other),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CrsType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CrsType::Geodetic => "Geodetic CRS",
+            CrsType::Geocentric => "Geocentric CRS",
+            CrsType::Geographic2D => "Geographic 2D CRS",
+            CrsType::Geographic3D => "Geographic 3D CRS",
+            CrsType::Vertical => "Vertical CRS",
+            CrsType::Projected => "Projected CRS",
+            CrsType::Compound => "Compound CRS",
+            CrsType::Temporal => "Temporal CRS",
+            CrsType::Engineering => "Engineering CRS",
+            CrsType::Bound => "Bound CRS",
+            CrsType::Other => "CRS",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The area in which a CRS or coordinate operation is valid
+#[derive(Debug, Clone, PartialEq)]
+pub struct AreaOfUse {
+    /// The bounding box of the area, in degrees
+    pub area: Area,
+    /// A description of the area, e.g. `World - N hemisphere - 12°E to 18°E - by country`
+    pub name: Option<String>,
+}
+
+/// An axis of the coordinate system of a CRS
+#[derive(Debug, Clone, PartialEq)]
+pub struct Axis {
+    /// The name of the axis, e.g. `Easting`
+    pub name: String,
+    /// The abbreviation of the axis, e.g. `E`
+    pub abbreviation: String,
+    /// The direction of the axis, e.g. `east`
+    pub direction: String,
+    /// The unit of the coordinates along the axis, e.g. `metre`
+    pub unit: Unit,
+}
+
+/// Get the area of use of a CRS or coordinate operation
+pub(crate) unsafe fn area_of_use_of(
+    ctx: *mut PJ_CONTEXT,
+    obj: *const PJconsts,
+) -> Option<AreaOfUse> {
+    let (mut west, mut south, mut east, mut north) = (0.0, 0.0, 0.0, 0.0);
+    let mut name = ptr::null();
+    let found = proj_get_area_of_use(
+        ctx, obj, &mut west, &mut south, &mut east, &mut north, &mut name,
+    );
+    // an unknown bounding box is reported as -1000
+    if found == 0 || west == -1000.0 {
+        return None;
+    }
+    Some(AreaOfUse {
+        area: Area::new(west, south, east, north),
+        name: if name.is_null() {
+            None
+        } else {
+            Some(_string(name)).filter(|name| !name.is_empty())
+        },
+    })
+}
+
+/// Get the axes of a single CRS, or of each component of a compound CRS
+unsafe fn axes_of(ctx: *mut PJ_CONTEXT, crs: *const PJconsts, axes: &mut Vec<Axis>) {
+    if proj_get_type(crs) == PJ_TYPE_PJ_TYPE_COMPOUND_CRS {
+        for index in 0..2 {
+            let sub_crs = proj_crs_get_sub_crs(ctx, crs, index);
+            if !sub_crs.is_null() {
+                axes_of(ctx, sub_crs, axes);
+                proj_destroy(sub_crs);
+            }
+        }
+        return;
+    }
+    let cs = proj_crs_get_coordinate_system(ctx, crs);
+    if cs.is_null() {
+        return;
+    }
+    for index in 0..proj_cs_get_axis_count(ctx, cs) {
+        let mut name = ptr::null();
+        let mut abbreviation = ptr::null();
+        let mut direction = ptr::null();
+        let mut unit_factor = 0.0;
+        let mut unit_name = ptr::null();
+        let found = proj_cs_get_axis_info(
+            ctx,
+            cs,
+            index,
+            &mut name,
+            &mut abbreviation,
+            &mut direction,
+            &mut unit_factor,
+            &mut unit_name,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        if found == 0 {
+            continue;
+        }
+        let string = |raw_ptr: *const c_char| {
+            if raw_ptr.is_null() {
+                String::new()
+            } else {
+                _string(raw_ptr)
+            }
+        };
+        axes.push(Axis {
+            name: string(name),
+            abbreviation: string(abbreviation),
+            direction: string(direction),
+            unit: Unit::new(&string(unit_name), unit_factor),
+        });
+    }
+    proj_destroy(cs);
+}
+
+/// Get the ellipsoid of a CRS or datum
+unsafe fn ellipsoid_of(ctx: *mut PJ_CONTEXT, obj: *const PJconsts) -> Option<Ellipsoid> {
+    let ellps = proj_get_ellipsoid(ctx, obj);
+    if ellps.is_null() {
+        return None;
+    }
+    let mut semi_major = 0.0;
+    let mut semi_minor = 0.0;
+    let mut is_computed: c_int = 0;
+    let mut inv_flattening = 0.0;
+    let found = proj_ellipsoid_get_parameters(
+        ctx,
+        ellps,
+        &mut semi_major,
+        &mut semi_minor,
+        &mut is_computed,
+        &mut inv_flattening,
+    );
+    let name = proj_get_name(ellps);
+    let ellipsoid = if found == 1 {
+        Some(Ellipsoid {
+            name: if name.is_null() {
+                String::new()
+            } else {
+                _string(name)
+            },
+            semi_major,
+            inv_flattening,
+        })
+    } else {
+        None
+    };
+    proj_destroy(ellps);
+    ellipsoid
+}
+
+/// Get the prime meridian of a CRS or datum
+unsafe fn prime_meridian_of(ctx: *mut PJ_CONTEXT, obj: *const PJconsts) -> Option<PrimeMeridian> {
+    let pm = proj_get_prime_meridian(ctx, obj);
+    if pm.is_null() {
+        return None;
+    }
+    let mut longitude = 0.0;
+    let mut factor = 0.0;
+    let mut unit_name = ptr::null();
+    let found =
+        proj_prime_meridian_get_parameters(ctx, pm, &mut longitude, &mut factor, &mut unit_name);
+    let name = proj_get_name(pm);
+    let prime_meridian = if found == 1 {
+        Some(PrimeMeridian {
+            name: if name.is_null() {
+                String::new()
+            } else {
+                _string(name)
+            },
+            longitude,
+            unit: Unit {
+                name: if unit_name.is_null() {
+                    String::new()
+                } else {
+                    _string(unit_name)
+                },
+                factor,
+            },
+        })
+    } else {
+        None
+    };
+    proj_destroy(pm);
+    prime_meridian
+}
+
+/// A builder for geographic coordinate reference systems
+///
+/// The resulting CRS can be passed straight to [`Proj::new_crs_to_crs`](struct.Proj.html#method.new_crs_to_crs),
+/// or to [`Proj::new_known_crs`](struct.Proj.html#method.new_known_crs) as its
+/// [WKT representation](struct.Proj.html#method.to_wkt).
+///
+/// ```rust
+/// use proj::{Ellipsoid, GeographicCrs};
+/// let crs = GeographicCrs::new("My WGS 84", "World Geodetic System 1984", Ellipsoid::wgs84())
+///     .build()
+///     .unwrap();
+/// assert!(crs.to_wkt().unwrap().starts_with("GEOGCRS[\"My WGS 84\""));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeographicCrs {
+    name: String,
+    datum: String,
+    ellipsoid: Ellipsoid,
+    prime_meridian: (String, f64),
+    angular_unit: Unit,
+}
+
+impl GeographicCrs {
+    /// Start building a geographic CRS with the given datum and ellipsoid
+    ///
+    /// The prime meridian defaults to Greenwich, and the angular unit to degrees.
+    pub fn new(name: &str, datum: &str, ellipsoid: Ellipsoid) -> Self {
+        GeographicCrs {
+            name: name.to_string(),
+            datum: datum.to_string(),
+            ellipsoid,
+            prime_meridian: ("Greenwich".to_string(), 0.0),
+            angular_unit: Unit::degree(),
+        }
+    }
+
+    /// Set the prime meridian, with its longitude from Greenwich in the angular unit of the CRS
+    pub fn prime_meridian(mut self, name: &str, longitude: f64) -> Self {
+        self.prime_meridian = (name.to_string(), longitude);
+        self
+    }
+
+    /// Set the angular unit of the CRS
+    pub fn angular_unit(mut self, unit: Unit) -> Self {
+        self.angular_unit = unit;
+        self
+    }
+
+    /// Create the CRS
+    ///
+    /// The axis order of the CRS is latitude, longitude.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn build(&self) -> Option<Proj> {
+        let ctx = unsafe { proj_context_create() };
+        let crs = unsafe { self.create(ctx) };
+        Proj::from_raw(ctx, crs)
+    }
+
+    unsafe fn create(&self, ctx: *mut PJ_CONTEXT) -> *mut PJconsts {
+        let name = CString::new(self.name.as_bytes()).unwrap();
+        let datum = CString::new(self.datum.as_bytes()).unwrap();
+        let ellps = CString::new(self.ellipsoid.name.as_bytes()).unwrap();
+        let pm = CString::new(self.prime_meridian.0.as_bytes()).unwrap();
+        let unit = CString::new(self.angular_unit.name.as_bytes()).unwrap();
+        let cs = proj_create_ellipsoidal_2D_cs(
+            ctx,
+            PJ_ELLIPSOIDAL_CS_2D_TYPE_PJ_ELLPS2D_LATITUDE_LONGITUDE,
+            unit.as_ptr(),
+            self.angular_unit.factor,
+        );
+        if cs.is_null() {
+            return ptr::null_mut();
+        }
+        let crs = proj_create_geographic_crs(
+            ctx,
+            name.as_ptr(),
+            datum.as_ptr(),
+            ellps.as_ptr(),
+            self.ellipsoid.semi_major,
+            self.ellipsoid.inv_flattening,
+            pm.as_ptr(),
+            self.prime_meridian.1,
+            unit.as_ptr(),
+            self.angular_unit.factor,
+            cs,
+        );
+        proj_destroy(cs);
+        crs
+    }
+}
+
+/// A map projection method and its parameters, used to define a [`ProjectedCrs`](struct.ProjectedCrs.html)
+///
+/// Angles are in degrees, and false eastings and northings are in the linear unit of the projected CRS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Conversion {
+    /// Universal Transverse Mercator
+    Utm { zone: u8, north: bool },
+    /// Transverse Mercator
+    TransverseMercator {
+        lat_0: f64,
+        lon_0: f64,
+        scale: f64,
+        false_easting: f64,
+        false_northing: f64,
+    },
+    /// Lambert Conic Conformal with one standard parallel
+    LambertConicConformal1SP {
+        lat_0: f64,
+        lon_0: f64,
+        scale: f64,
+        false_easting: f64,
+        false_northing: f64,
+    },
+    /// Lambert Conic Conformal with two standard parallels
+    LambertConicConformal2SP {
+        lat_0: f64,
+        lon_0: f64,
+        lat_1: f64,
+        lat_2: f64,
+        false_easting: f64,
+        false_northing: f64,
+    },
+    /// Oblique Stereographic
+    ObliqueStereographic {
+        lat_0: f64,
+        lon_0: f64,
+        scale: f64,
+        false_easting: f64,
+        false_northing: f64,
+    },
+    /// Lambert Azimuthal Equal Area
+    LambertAzimuthalEqualArea {
+        lat_0: f64,
+        lon_0: f64,
+        false_easting: f64,
+        false_northing: f64,
+    },
+}
+
+impl Conversion {
+    unsafe fn create(&self, ctx: *mut PJ_CONTEXT, linear_unit: &Unit) -> *mut PJconsts {
+        let degree = Unit::degree();
+        let ang = CString::new(degree.name.as_bytes()).unwrap();
+        let lin = CString::new(linear_unit.name.as_bytes()).unwrap();
+        let (ang, ang_factor) = (ang.as_ptr(), degree.factor);
+        let (lin, lin_factor) = (lin.as_ptr(), linear_unit.factor);
+        match *self {
+            Conversion::Utm { zone, north } => {
+                proj_create_conversion_utm(ctx, c_int::from(zone), c_int::from(north))
+            }
+            Conversion::TransverseMercator {
+                lat_0,
+                lon_0,
+                scale,
+                false_easting,
+                false_northing,
+            } => proj_create_conversion_transverse_mercator(
+                ctx,
+                lat_0,
+                lon_0,
+                scale,
+                false_easting,
+                false_northing,
+                ang,
+                ang_factor,
+                lin,
+                lin_factor,
+            ),
+            Conversion::LambertConicConformal1SP {
+                lat_0,
+                lon_0,
+                scale,
+                false_easting,
+                false_northing,
+            } => proj_create_conversion_lambert_conic_conformal_1sp(
+                ctx,
+                lat_0,
+                lon_0,
+                scale,
+                false_easting,
+                false_northing,
+                ang,
+                ang_factor,
+                lin,
+                lin_factor,
+            ),
+            Conversion::LambertConicConformal2SP {
+                lat_0,
+                lon_0,
+                lat_1,
+                lat_2,
+                false_easting,
+                false_northing,
+            } => proj_create_conversion_lambert_conic_conformal_2sp(
+                ctx,
+                lat_0,
+                lon_0,
+                lat_1,
+                lat_2,
+                false_easting,
+                false_northing,
+                ang,
+                ang_factor,
+                lin,
+                lin_factor,
+            ),
+            Conversion::ObliqueStereographic {
+                lat_0,
+                lon_0,
+                scale,
+                false_easting,
+                false_northing,
+            } => proj_create_conversion_oblique_stereographic(
+                ctx,
+                lat_0,
+                lon_0,
+                scale,
+                false_easting,
+                false_northing,
+                ang,
+                ang_factor,
+                lin,
+                lin_factor,
+            ),
+            Conversion::LambertAzimuthalEqualArea {
+                lat_0,
+                lon_0,
+                false_easting,
+                false_northing,
+            } => proj_create_conversion_lambert_azimuthal_equal_area(
+                ctx,
+                lat_0,
+                lon_0,
+                false_easting,
+                false_northing,
+                ang,
+                ang_factor,
+                lin,
+                lin_factor,
+            ),
+        }
+    }
+}
+
+/// A builder for projected coordinate reference systems
+///
+/// The resulting CRS can be used as input or output of [`Proj::new_crs_to_crs`](struct.Proj.html#method.new_crs_to_crs),
+/// or of [`Proj::new_known_crs`](struct.Proj.html#method.new_known_crs) via its
+/// [WKT representation](struct.Proj.html#method.to_wkt):
+///
+/// ```rust
+/// use proj::{Conversion, Proj, ProjectedCrs};
+/// extern crate geo_types;
+/// use geo_types::Point;
+/// # use assert_approx_eq::assert_approx_eq;
+/// let wgs84 = Proj::new("EPSG:4326").unwrap();
+/// let site = ProjectedCrs::new(
+///     "Site grid",
+///     &wgs84,
+///     Conversion::TransverseMercator {
+///         lat_0: 51.0,
+///         lon_0: -1.5,
+///         scale: 1.0,
+///         false_easting: 10000.0,
+///         false_northing: 20000.0,
+///     },
+/// )
+/// .build()
+/// .unwrap();
+/// let to_site = Proj::new_crs_to_crs(&wgs84, &site, None).unwrap();
+/// let origin = to_site.convert(Point::new(-1.5f64, 51.0f64)).unwrap();
+/// assert_approx_eq!(origin.x(), 10000.0f64, 1.0e-6);
+/// assert_approx_eq!(origin.y(), 20000.0f64, 1.0e-6);
+/// ```
+pub struct ProjectedCrs<'a> {
+    name: String,
+    base: &'a Proj,
+    conversion: Conversion,
+    linear_unit: Unit,
+}
+
+impl<'a> ProjectedCrs<'a> {
+    /// Start building a projected CRS based on a geographic CRS
+    ///
+    /// `base` can be any geodetic CRS, e.g. one created using `Proj::new("EPSG:4326")` or a
+    /// [`GeographicCrs`](struct.GeographicCrs.html). The linear unit defaults to metres.
+    /// `base` is copied into the context of the new CRS, so it isn't needed once the CRS is built.
+    pub fn new(name: &str, base: &'a Proj, conversion: Conversion) -> Self {
+        ProjectedCrs {
+            name: name.to_string(),
+            base,
+            conversion,
+            linear_unit: Unit::metre(),
+        }
+    }
+
+    /// Set the linear unit of the CRS, which is also the unit of false eastings and northings
+    pub fn linear_unit(mut self, unit: Unit) -> Self {
+        self.linear_unit = unit;
+        self
+    }
+
+    /// Create the CRS
+    ///
+    /// The axis order of the CRS is easting, northing.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn build(&self) -> Option<Proj> {
+        let name = CString::new(self.name.as_bytes()).unwrap();
+        let unit = CString::new(self.linear_unit.name.as_bytes()).unwrap();
+        let ctx = unsafe { proj_context_create() };
+        let crs = unsafe {
+            let conversion = self.conversion.create(ctx, &self.linear_unit);
+            let cs = proj_create_cartesian_2D_cs(
+                ctx,
+                PJ_CART2D_TYPE_PJ_CART2D_EASTING_NORTHING,
+                unit.as_ptr(),
+                self.linear_unit.factor,
+            );
+            let base = clone_into(ctx, self.base);
+            let crs = if conversion.is_null() || cs.is_null() || base.is_null() {
+                ptr::null_mut()
+            } else {
+                proj_create_projected_crs(ctx, name.as_ptr(), base, conversion, cs)
+            };
+            for obj in [conversion, cs, base].iter().filter(|obj| !obj.is_null()) {
+                proj_destroy(*obj);
+            }
+            crs
+        };
+        Proj::from_raw(ctx, crs)
+    }
+}
+
+/// Copy a CRS into `ctx`
+///
+/// Each `Proj` owns its context, so a CRS which is a component of a new CRS is
+/// copied into the new CRS's context rather than shared with the `Proj` it came from.
+unsafe fn clone_into(ctx: *mut PJ_CONTEXT, crs: &Proj) -> *mut PJconsts {
+    proj_clone(ctx, crs.c_proj)
+}
+
+/// A builder for derived geographic coordinate reference systems, whose coordinates are
+/// those of a base geographic CRS rotated to a new pole (e.g. the rotated grids of weather models)
+///
+/// The pole rotation follows the GRIB convention: it is defined by the position of the
+/// southern pole of the derived CRS, in the base CRS, and an optional rotation about the
+/// new polar axis. As with [`GeographicCrs`](struct.GeographicCrs.html), the resulting CRS can be
+/// passed to [`Proj::new_crs_to_crs`](struct.Proj.html#method.new_crs_to_crs).
+///
+/// ```rust
+/// use proj::{DerivedGeographicCrs, Proj};
+/// let wgs84 = Proj::new("EPSG:4326").unwrap();
+/// let rotated = DerivedGeographicCrs::new("Rotated WGS 84", &wgs84, -30.0, 15.0)
+///     .build()
+///     .unwrap();
+/// assert!(rotated.to_wkt().unwrap().contains("DERIVINGCONVERSION"));
+/// ```
+pub struct DerivedGeographicCrs<'a> {
+    name: String,
+    base: &'a Proj,
+    south_pole_latitude: f64,
+    south_pole_longitude: f64,
+    axis_rotation: f64,
+}
+
+impl<'a> DerivedGeographicCrs<'a> {
+    /// Start building a derived geographic CRS whose southern pole is at the given latitude and
+    /// longitude (in degrees) of the `base` geographic CRS
+    ///
+    /// The axis rotation defaults to `0`.
+    pub fn new(
+        name: &str,
+        base: &'a Proj,
+        south_pole_latitude: f64,
+        south_pole_longitude: f64,
+    ) -> Self {
+        DerivedGeographicCrs {
+            name: name.to_string(),
+            base,
+            south_pole_latitude,
+            south_pole_longitude,
+            axis_rotation: 0.0,
+        }
+    }
+
+    /// Set the rotation about the new polar axis, in degrees
+    pub fn axis_rotation(mut self, axis_rotation: f64) -> Self {
+        self.axis_rotation = axis_rotation;
+        self
+    }
+
+    /// Create the CRS
+    ///
+    /// The axis order of the CRS is latitude, longitude, in degrees.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn build(&self) -> Option<Proj> {
+        let name = CString::new(self.name.as_bytes()).unwrap();
+        let degree = Unit::degree();
+        let unit = CString::new(degree.name.as_bytes()).unwrap();
+        let ctx = unsafe { proj_context_create() };
+        let crs = unsafe {
+            let conversion = proj_create_conversion_pole_rotation_grib_convention(
+                ctx,
+                self.south_pole_latitude,
+                self.south_pole_longitude,
+                self.axis_rotation,
+                unit.as_ptr(),
+                degree.factor,
+            );
+            let cs = proj_create_ellipsoidal_2D_cs(
+                ctx,
+                PJ_ELLIPSOIDAL_CS_2D_TYPE_PJ_ELLPS2D_LATITUDE_LONGITUDE,
+                unit.as_ptr(),
+                degree.factor,
+            );
+            let base = clone_into(ctx, self.base);
+            let crs = if conversion.is_null() || cs.is_null() || base.is_null() {
+                ptr::null_mut()
+            } else {
+                proj_create_derived_geographic_crs(ctx, name.as_ptr(), base, conversion, cs)
+            };
+            for obj in [conversion, cs, base].iter().filter(|obj| !obj.is_null()) {
+                proj_destroy(*obj);
+            }
+            crs
+        };
+        Proj::from_raw(ctx, crs)
+    }
+}
+
+/// A builder for compound coordinate reference systems, made of a horizontal and a vertical CRS
+///
+/// Transformations to or from the resulting CRS can be created using
+/// [`Proj::new_crs_to_crs`](struct.Proj.html#method.new_crs_to_crs).
+///
+/// ```rust
+/// use proj::{CompoundCrs, Proj};
+/// let nad83_utm10 = Proj::new("EPSG:26910").unwrap();
+/// let navd88 = Proj::new("EPSG:5703").unwrap();
+/// let compound = CompoundCrs::new("NAD83 / UTM zone 10N + NAVD88 height", &nad83_utm10, &navd88)
+///     .build()
+///     .unwrap();
+/// assert_eq!(compound.sub_crs(1).unwrap().name().unwrap(), "NAVD88 height");
+/// ```
+pub struct CompoundCrs<'a> {
+    name: String,
+    horizontal: &'a Proj,
+    vertical: &'a Proj,
+}
+
+impl<'a> CompoundCrs<'a> {
+    /// Start building a compound CRS from a horizontal (geographic or projected) CRS and a vertical CRS
+    ///
+    /// Compound CRS which are in the `PROJ` database can also be created directly, using e.g.
+    /// `Proj::new("EPSG:26910+5703")`.
+    /// `horizontal` and `vertical` are copied into the context of the new CRS.
+    pub fn new(name: &str, horizontal: &'a Proj, vertical: &'a Proj) -> Self {
+        CompoundCrs {
+            name: name.to_string(),
+            horizontal,
+            vertical,
+        }
+    }
+
+    /// Create the CRS
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn build(&self) -> Option<Proj> {
+        let name = CString::new(self.name.as_bytes()).unwrap();
+        let ctx = unsafe { proj_context_create() };
+        let crs = unsafe {
+            let horizontal = clone_into(ctx, self.horizontal);
+            let vertical = clone_into(ctx, self.vertical);
+            let crs = if horizontal.is_null() || vertical.is_null() {
+                ptr::null_mut()
+            } else {
+                proj_create_compound_crs(ctx, name.as_ptr(), horizontal, vertical)
+            };
+            for obj in [horizontal, vertical].iter().filter(|obj| !obj.is_null()) {
+                proj_destroy(*obj);
+            }
+            crs
+        };
+        Proj::from_raw(ctx, crs)
+    }
+}
+
+/// The EPSG code among the identifiers of an object
+unsafe fn epsg_code_of(obj: *const PJconsts) -> Option<u32> {
+    (0..)
+        .map(|index| {
+            (
+                proj_get_id_auth_name(obj, index),
+                proj_get_id_code(obj, index),
+            )
+        })
+        .take_while(|(auth_name, code)| !auth_name.is_null() && !code.is_null())
+        .find(|(auth_name, _)| _string(*auth_name) == "EPSG")
+        .and_then(|(_, code)| _string(code).parse().ok())
+}
+
+/// The EPSG code of the EPSG CRS an unidentified CRS is most likely identical to
+unsafe fn identify_epsg_code(ctx: *mut PJ_CONTEXT, crs: *const PJconsts) -> Option<u32> {
+    // matches with a lower confidence differ by more than their name or axis order
+    const MIN_CONFIDENCE: c_int = 70;
+    let epsg = CString::new("EPSG").unwrap();
+    let mut confidence: *mut c_int = ptr::null_mut();
+    let matches = proj_identify(ctx, crs, epsg.as_ptr(), ptr::null(), &mut confidence);
+    if matches.is_null() {
+        return None;
+    }
+    // matches are sorted by decreasing confidence
+    let mut code = None;
+    if proj_list_get_count(matches) > 0 && !confidence.is_null() && *confidence >= MIN_CONFIDENCE {
+        let best = proj_list_get(ctx, matches, 0);
+        if !best.is_null() {
+            code = epsg_code_of(best);
+            proj_destroy(best);
+        }
+    }
+    proj_int_list_destroy(confidence);
+    proj_list_destroy(matches);
+    code
+}
+
+impl Proj {
+    /// Export this object as WKT (WKT2:2019)
+    ///
+    /// The result can be used as input to [`new_known_crs`](#method.new_known_crs).
+    /// Returns `None` if the object can't be represented as WKT.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn to_wkt(&self) -> Option<String> {
+        let wkt =
+            unsafe { proj_as_wkt(self.ctx, self.c_proj, PJ_WKT_TYPE_PJ_WKT2_2019, ptr::null()) };
+        if wkt.is_null() {
+            None
+        } else {
+            Some(_string(wkt))
+        }
+    }
+
+    /// The name of this object, if it has one
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn name(&self) -> Option<String> {
+        let name = unsafe { proj_get_name(self.c_proj) };
+        if name.is_null() {
+            None
+        } else {
+            Some(_string(name))
+        }
+    }
+
+    /// The ellipsoid of this CRS or, for a transformation between known CRS
+    /// (see [`new_known_crs`](#method.new_known_crs)), of its source CRS
+    ///
+    /// Returns `None` if the object has no ellipsoid.
+    ///
+    /// ```rust
+    /// use proj::Proj;
+    /// let ellipsoid = Proj::new("EPSG:4326").unwrap().ellipsoid().unwrap();
+    /// assert_eq!(ellipsoid.name, "WGS 84");
+    /// assert_eq!(ellipsoid.semi_major, 6378137.0);
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn ellipsoid(&self) -> Option<Ellipsoid> {
+        unsafe { self.with_crs(|crs| ellipsoid_of(self.ctx, crs)) }
+    }
+
+    /// The datum of this CRS or, for a transformation between known CRS, of its source CRS
+    ///
+    /// For a compound CRS, this is the datum of its horizontal component.
+    /// Returns `None` if the object has no datum.
+    ///
+    /// **Note:** datum ensembles were introduced in PROJ 7.2, and are not supported
+    /// by the version of PROJ these bindings target.
+    ///
+    /// ```rust
+    /// use proj::Proj;
+    /// let datum = Proj::new("EPSG:27700").unwrap().datum().unwrap();
+    /// assert_eq!(datum.name, "Ordnance Survey of Great Britain 1936");
+    /// assert_eq!(datum.ellipsoid.unwrap().name, "Airy 1830");
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn datum(&self) -> Option<Datum> {
+        unsafe {
+            self.with_crs(|crs| {
+                let mut datum = proj_crs_get_datum(self.ctx, crs);
+                if datum.is_null() {
+                    datum = proj_crs_get_horizontal_datum(self.ctx, crs);
+                }
+                if datum.is_null() {
+                    return None;
+                }
+                let name = proj_get_name(datum);
+                let result = Datum {
+                    name: if name.is_null() {
+                        String::new()
+                    } else {
+                        _string(name)
+                    },
+                    ellipsoid: ellipsoid_of(self.ctx, datum),
+                    prime_meridian: prime_meridian_of(self.ctx, datum),
+                };
+                proj_destroy(datum);
+                Some(result)
+            })
+        }
+    }
+
+    /// The prime meridian of this CRS or, for a transformation between known CRS, of its source CRS
+    ///
+    /// Returns `None` if the object has no prime meridian.
+    ///
+    /// ```rust
+    /// use proj::Proj;
+    /// let paris = Proj::new("EPSG:4807").unwrap().prime_meridian().unwrap();
+    /// assert_eq!(paris.name, "Paris");
+    /// assert_eq!(paris.unit.name, "grad");
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn prime_meridian(&self) -> Option<PrimeMeridian> {
+        unsafe { self.with_crs(|crs| prime_meridian_of(self.ctx, crs)) }
+    }
+
+    /// Call `f` with this CRS or, for a transformation between known CRS, its source CRS
+    unsafe fn with_crs<R, F>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(*const PJconsts) -> Option<R>,
+    {
+        if proj_is_crs(self.c_proj) == 1 {
+            return f(self.c_proj);
+        }
+        let source = proj_get_source_crs(self.ctx, self.c_proj);
+        if source.is_null() {
+            return None;
+        }
+        let result = f(source);
+        proj_destroy(source);
+        result
+    }
+
+    /// The EPSG code of this CRS or, for a transformation between known CRS, of its target CRS
+    ///
+    /// If the CRS has no EPSG identifier (e.g. because its axis order has been normalised by
+    /// [`new_known_crs`](#method.new_known_crs)), the code of the EPSG CRS it is most likely
+    /// identical to is returned. Returns `None` if no EPSG CRS matches.
+    ///
+    /// ```rust
+    /// use proj::Proj;
+    /// let ft_to_m = Proj::new_known_crs("EPSG:2230", "EPSG:26946", None).unwrap();
+    /// assert_eq!(ft_to_m.target_epsg_code(), Some(26946));
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn target_epsg_code(&self) -> Option<u32> {
+        unsafe {
+            let target = if proj_is_crs(self.c_proj) == 1 {
+                None
+            } else {
+                Some(proj_get_target_crs(self.ctx, self.c_proj))
+            };
+            let crs = target.unwrap_or(self.c_proj);
+            if crs.is_null() {
+                return None;
+            }
+            let code = epsg_code_of(crs).or_else(|| identify_epsg_code(self.ctx, crs));
+            if let Some(target) = target {
+                proj_destroy(target);
+            }
+            code
+        }
+    }
+
+    /// Get a component of a compound CRS
+    ///
+    /// `index` `0` is the horizontal CRS, and `1` is the vertical CRS.
+    /// Returns `None` if this object isn't a compound CRS, or if `index` is out of range.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn sub_crs(&self, index: usize) -> Option<Proj> {
+        let ctx = unsafe { proj_context_create() };
+        let crs = unsafe { proj_crs_get_sub_crs(ctx, self.c_proj, index as c_int) };
+        Proj::from_raw(ctx, crs)
+    }
+
+    /// The type of this CRS
+    ///
+    /// Returns `None` if this object isn't a CRS, e.g. because it is a transformation.
+    ///
+    /// ```rust
+    /// use proj::{CrsType, Proj};
+    /// let utm = Proj::new("EPSG:32633").unwrap();
+    /// assert_eq!(utm.crs_type(), Some(CrsType::Projected));
+    /// assert_eq!(utm.crs_type().unwrap().to_string(), "Projected CRS");
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn crs_type(&self) -> Option<CrsType> {
+        CrsType::from_raw(unsafe { proj_get_type(self.c_proj) })
+    }
+
+    /// Whether this object is deprecated by the authority which defined it
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn is_deprecated(&self) -> bool {
+        unsafe { proj_is_deprecated(self.c_proj) == 1 }
+    }
+
+    /// The area of use of this CRS or coordinate operation
+    ///
+    /// Returns `None` if the object has no known area of use, e.g. because it was
+    /// defined by a PROJ string.
+    ///
+    /// ```rust
+    /// use proj::Proj;
+    /// let area = Proj::new("EPSG:32633").unwrap().area_of_use().unwrap();
+    /// assert_eq!(area.area.west(), 12.0);
+    /// assert_eq!(area.area.east(), 18.0);
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn area_of_use(&self) -> Option<AreaOfUse> {
+        unsafe { area_of_use_of(self.ctx, self.c_proj) }
+    }
+
+    /// The axes of this CRS or, for a transformation between known CRS, of its source CRS
+    ///
+    /// The axes of a compound CRS are those of its horizontal CRS, followed by those of
+    /// its vertical CRS. Returns an empty `Vec` if the object has no coordinate system.
+    ///
+    /// ```rust
+    /// use proj::Proj;
+    /// let axes = Proj::new("EPSG:4326").unwrap().axes();
+    /// assert_eq!(axes[0].name, "Geodetic latitude");
+    /// assert_eq!(axes[0].direction, "north");
+    /// assert_eq!(axes[1].unit.name, "degree");
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn axes(&self) -> Vec<Axis> {
+        let mut axes = Vec::new();
+        unsafe {
+            self.with_crs(|crs| {
+                axes_of(self.ctx, crs, &mut axes);
+                Some(())
+            });
+        }
+        axes
+    }
+
+    /// Export this object as PROJJSON
+    ///
+    /// Returns `None` if the object can't be represented as PROJJSON.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn to_projjson(&self) -> Option<String> {
+        let json = unsafe { proj_as_projjson(self.ctx, self.c_proj, ptr::null()) };
+        if json.is_null() {
+            None
+        } else {
+            Some(_string(json))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        CompoundCrs, Conversion, CrsType, DerivedGeographicCrs, Ellipsoid, GeographicCrs, Proj,
+        ProjectedCrs, Unit,
+    };
+    use geo_types::Point;
+
+    fn assert_almost_eq(a: f64, b: f64) {
+        let f: f64 = a / b;
+        assert!(f < 1.00001);
+        assert!(f > 0.99999);
+    }
+
+    #[test]
+    fn test_datum() {
+        let osgb = Proj::new("EPSG:27700").unwrap();
+        let datum = osgb.datum().unwrap();
+        assert_eq!(datum.name, "Ordnance Survey of Great Britain 1936");
+        let airy = datum.ellipsoid.unwrap();
+        assert_eq!(airy.semi_major, 6377563.396);
+        assert_almost_eq(airy.inv_flattening, 299.3249646);
+        assert_eq!(datum.prime_meridian.unwrap().name, "Greenwich");
+        // the datum of a transformation is that of its source CRS
+        let wgs84_to_osgb = Proj::new_known_crs("EPSG:4326", "EPSG:27700", None).unwrap();
+        assert_eq!(
+            wgs84_to_osgb.datum().unwrap().name,
+            "World Geodetic System 1984"
+        );
+    }
+
+    #[test]
+    fn test_prime_meridian() {
+        let greenwich = Proj::new("EPSG:4326").unwrap().prime_meridian().unwrap();
+        assert_eq!(greenwich.name, "Greenwich");
+        assert_eq!(greenwich.longitude_degrees(), 0.0);
+        // NTF (Paris) has its prime meridian defined in grads
+        let paris = Proj::new("EPSG:4807").unwrap().prime_meridian().unwrap();
+        assert_eq!(paris.name, "Paris");
+        assert_eq!(paris.unit.name, "grad");
+        assert_almost_eq(paris.longitude, 2.5969213);
+        assert_almost_eq(paris.longitude_degrees(), 2.33722917);
+    }
+
+    #[test]
+    fn test_projected_crs_matches_epsg() {
+        let wgs84 = GeographicCrs::new("WGS 84", "World Geodetic System 1984", Ellipsoid::wgs84())
+            .build()
+            .unwrap();
+        let utm_11n = ProjectedCrs::new(
+            "Custom UTM zone 11N",
+            &wgs84,
+            Conversion::TransverseMercator {
+                lat_0: 0.0,
+                lon_0: -117.0,
+                scale: 0.9996,
+                false_easting: 500000.0,
+                false_northing: 0.0,
+            },
+        )
+        .build()
+        .unwrap();
+        let custom = Proj::new_known_crs("EPSG:4326", &utm_11n.to_wkt().unwrap(), None).unwrap();
+        let epsg = Proj::new_known_crs("EPSG:4326", "EPSG:32611", None).unwrap();
+        let a = custom.convert(Point::new(-116.5, 34.0)).unwrap();
+        let b = epsg.convert(Point::new(-116.5, 34.0)).unwrap();
+        assert_almost_eq(a.x(), b.x());
+        assert_almost_eq(a.y(), b.y());
+    }
+
+    #[test]
+    fn test_projected_crs_units() {
+        let wgs84 = Proj::new("EPSG:4326").unwrap();
+        let utm_ft = ProjectedCrs::new(
+            "UTM zone 11N (US survey feet)",
+            &wgs84,
+            Conversion::Utm {
+                zone: 11,
+                north: true,
+            },
+        )
+        .linear_unit(Unit::us_survey_foot())
+        .build()
+        .unwrap();
+        let to_ft = Proj::new_crs_to_crs(&wgs84, &utm_ft, None).unwrap();
+        let to_m = Proj::new_known_crs("EPSG:4326", "EPSG:32611", None).unwrap();
+        let ft = to_ft.convert(Point::new(-116.5, 34.0)).unwrap();
+        let m = to_m.convert(Point::new(-116.5, 34.0)).unwrap();
+        assert_almost_eq(ft.x() * Unit::us_survey_foot().factor, m.x());
+        assert_almost_eq(ft.y() * Unit::us_survey_foot().factor, m.y());
+    }
+
+    #[test]
+    fn test_derived_geographic_crs() {
+        let wgs84 = Proj::new("EPSG:4326").unwrap();
+        let rotated = DerivedGeographicCrs::new("Rotated WGS 84", &wgs84, -30.0, 15.0)
+            .build()
+            .unwrap();
+        // the base CRS was copied into the new CRS
+        drop(wgs84);
+        assert_eq!(rotated.name().unwrap(), "Rotated WGS 84");
+        let wgs84 = Proj::new("EPSG:4326").unwrap();
+        let to_rotated = Proj::new_crs_to_crs(&wgs84, &rotated, None).unwrap();
+        // the southern pole of the rotated CRS, and its antipode, the northern pole
+        let south = to_rotated.convert(Point::new(15.0f64, -30.0)).unwrap();
+        assert!((south.y() + 90.0).abs() < 1e-6);
+        let north = to_rotated.convert(Point::new(-165.0f64, 30.0)).unwrap();
+        assert!((north.y() - 90.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_ellipsoid() {
+        let wgs84 = Ellipsoid::wgs84();
+        assert_almost_eq(wgs84.semi_minor(), 6356752.314245179);
+        assert!(GeographicCrs::new(
+            "Sphere",
+            "Unknown",
+            Ellipsoid::new("Sphere", 6371000.0, 0.0)
+        )
+        .build()
+        .is_some());
+    }
+
+    #[test]
+    fn test_compound_crs() {
+        let nad83_utm10 = Proj::new("EPSG:26910").unwrap();
+        let navd88 = Proj::new("EPSG:5703").unwrap();
+        let compound = CompoundCrs::new("UTM 10N + NAVD88", &nad83_utm10, &navd88)
+            .build()
+            .unwrap();
+        assert_eq!(compound.name().unwrap(), "UTM 10N + NAVD88");
+        assert_eq!(
+            compound.sub_crs(0).unwrap().name().unwrap(),
+            "NAD83 / UTM zone 10N"
+        );
+        assert_eq!(
+            compound.sub_crs(1).unwrap().name().unwrap(),
+            "NAVD88 height"
+        );
+        assert!(compound.sub_crs(2).is_none());
+        assert!(nad83_utm10.sub_crs(0).is_none());
+    }
+
+    #[test]
+    fn test_compound_crs_height_units() {
+        // both CRSs use NAVD88 heights, so only the unit of the height changes, and no grid is needed
+        let nad83_utm10 = Proj::new("EPSG:26910").unwrap();
+        let navd88_ft = Proj::new("EPSG:6360").unwrap();
+        let navd88 = Proj::new("EPSG:5703").unwrap();
+        let feet = CompoundCrs::new("UTM 10N + NAVD88 (ftUS)", &nad83_utm10, &navd88_ft)
+            .build()
+            .unwrap();
+        let metres = CompoundCrs::new("UTM 10N + NAVD88", &nad83_utm10, &navd88)
+            .build()
+            .unwrap();
+        let to_metres = Proj::new_crs_to_crs(&feet, &metres, None).unwrap();
+        let (x, y, h) = to_metres
+            .convert_3d((550000.0f64, 5272000.0, 100.0))
+            .unwrap();
+        assert_almost_eq(x, 550000.0);
+        assert_almost_eq(y, 5272000.0);
+        assert_almost_eq(h, 100.0 * Unit::us_survey_foot().factor);
+    }
+
+    #[test]
+    #[ignore = "needs the NAVD88 geoid grid"]
+    fn test_compound_crs_heights() {
+        let nad83_utm10 = Proj::new("EPSG:26910").unwrap();
+        let navd88 = Proj::new("EPSG:5703").unwrap();
+        let compound = CompoundCrs::new("UTM 10N + NAVD88", &nad83_utm10, &navd88)
+            .build()
+            .unwrap();
+        // NAVD88 heights are converted to ellipsoidal heights using a geoid model
+        let wgs84 = Proj::new("EPSG:4979").unwrap();
+        let to_wgs84 = Proj::new_crs_to_crs(&compound, &wgs84, None).unwrap();
+        // Seattle, at 100 m above NAVD88
+        let (lon, lat, h) = to_wgs84
+            .convert_3d((550000.0f64, 5272000.0, 100.0))
+            .unwrap();
+        assert!((lon + 122.3).abs() < 0.1);
+        assert!((lat - 47.6).abs() < 0.1);
+        // the geoid is about 23 m below the ellipsoid here
+        assert!(h > 65.0 && h < 85.0);
+        // the same CRS from the database
+        let from_epsg = Proj::new_known_crs("EPSG:26910+5703", "EPSG:4979", None).unwrap();
+        let (_, _, h_epsg) = from_epsg
+            .convert_3d((550000.0f64, 5272000.0, 100.0))
+            .unwrap();
+        assert!((h - h_epsg).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_crs_type() {
+        let crs_type = |definition| Proj::new(definition).unwrap().crs_type();
+        assert_eq!(crs_type("EPSG:4326"), Some(CrsType::Geographic2D));
+        assert_eq!(crs_type("EPSG:4979"), Some(CrsType::Geographic3D));
+        assert_eq!(crs_type("EPSG:4978"), Some(CrsType::Geocentric));
+        assert_eq!(crs_type("EPSG:27700"), Some(CrsType::Projected));
+        assert_eq!(crs_type("EPSG:5703"), Some(CrsType::Vertical));
+        assert_eq!(crs_type("EPSG:7405"), Some(CrsType::Compound));
+        // a transformation isn't a CRS
+        let transformation = Proj::new_known_crs("EPSG:4326", "EPSG:27700", None).unwrap();
+        assert_eq!(transformation.crs_type(), None);
+        assert!(!Proj::new("EPSG:27700").unwrap().is_deprecated());
+    }
+
+    #[test]
+    fn test_area_of_use() {
+        let osgb = Proj::new("EPSG:27700").unwrap().area_of_use().unwrap();
+        assert!(osgb.name.unwrap().starts_with("United Kingdom"));
+        assert_almost_eq(osgb.area.south(), 49.75);
+        assert_almost_eq(osgb.area.north(), 61.01);
+        // a CRS defined by a PROJ string has no area of use
+        let custom = Proj::new("+proj=merc +ellps=WGS84 +type=crs").unwrap();
+        assert!(custom.area_of_use().is_none());
+    }
+
+    #[test]
+    fn test_axes() {
+        let osgb = Proj::new("EPSG:27700").unwrap().axes();
+        assert_eq!(osgb.len(), 2);
+        assert_eq!(osgb[0].name, "Easting");
+        assert_eq!(osgb[0].abbreviation, "E");
+        assert_eq!(osgb[1].direction, "north");
+        assert_eq!(osgb[1].unit, Unit::metre());
+        // the axes of a compound CRS are those of its components
+        let compound = Proj::new("EPSG:7405").unwrap().axes();
+        assert_eq!(compound.len(), 3);
+        assert_eq!(compound[2].direction, "up");
+    }
+}
@@ -0,0 +1,259 @@
+use crate::proj::{_string, ProjError};
+use geo_types::Point;
+use libc::{c_char, c_int};
+use proj_sys::{proj_dmstor, proj_rtodms};
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+/// Parse a DMS (degrees, minutes, seconds) angle, returning radians
+///
+/// Angles are written as e.g. `47d30'15.2"N` or `47°30'15.2"N`, and `S` and `W`
+/// hemispheres (or a leading `-`) denote negative angles. Minutes and seconds may be omitted.
+///
+///```rust
+/// # use assert_approx_eq::assert_approx_eq;
+/// extern crate proj;
+/// use proj::dms_to_radians;
+///
+/// let lat = dms_to_radians("47°30'15.2\"N").unwrap();
+/// assert_approx_eq!(lat.to_degrees(), 47.504222222, 1e-9);
+/// ```
+///
+/// # Safety
+/// This method contains unsafe code.
+pub fn dms_to_radians(dms: &str) -> Result<f64, ProjError> {
+    // PROJ only understands ASCII designators
+    let normalised = dms
+        .trim()
+        .replace('°', "d")
+        .replace('′', "'")
+        .replace('″', "\"");
+    let c_dms = CString::new(normalised.as_bytes())
+        .map_err(|_| ProjError::Angle(format!("invalid angle: {}", dms)))?;
+    let mut rest: *mut c_char = ptr::null_mut();
+    let radians = unsafe { proj_dmstor(c_dms.as_ptr(), &mut rest) };
+    let unparsed = if rest.is_null() {
+        String::new()
+    } else {
+        _string(rest)
+    };
+    if normalised.is_empty() || !radians.is_finite() || !unparsed.trim().is_empty() {
+        return Err(ProjError::Angle(format!("invalid angle: {}", dms)));
+    }
+    Ok(radians)
+}
+
+/// Parse a DMS (degrees, minutes, seconds) angle, returning degrees
+///
+/// See [`dms_to_radians`](fn.dms_to_radians.html) for the accepted format.
+pub fn dms_to_degrees(dms: &str) -> Result<f64, ProjError> {
+    dms_to_radians(dms).map(f64::to_degrees)
+}
+
+/// Parse a pair of DMS longitude and latitude angles into a `Point`, in radians
+///
+/// The result is suitable as input to [`Proj::project`](struct.Proj.html#method.project).
+///
+///```rust
+/// # use assert_approx_eq::assert_approx_eq;
+/// extern crate proj;
+/// use proj::{dms_point, Proj};
+///
+/// let stereo70 = Proj::new(
+///     "+proj=sterea +lat_0=46 +lon_0=25 +k=0.99975 +x_0=500000 +y_0=500000
+///     +ellps=krass +towgs84=33.4,-146.6,-76.3,-0.359,-0.053,0.844,-0.84 +units=m +no_defs",
+/// )
+/// .unwrap();
+/// let origin = dms_point("25dE", "46dN").unwrap();
+/// let result = stereo70.project(origin, false).unwrap();
+/// assert_approx_eq!(result.x(), 500000.0, 1e-6);
+/// assert_approx_eq!(result.y(), 500000.0, 1e-6);
+/// ```
+pub fn dms_point(lon: &str, lat: &str) -> Result<Point<f64>, ProjError> {
+    Ok(Point::new(dms_to_radians(lon)?, dms_to_radians(lat)?))
+}
+
+/// Format an angle in radians as DMS, using `PROJ`'s formatting
+///
+/// Seconds are given to at most three decimal places. `positive` and `negative` are the hemisphere
+/// letters appended to the angle, e.g. `'N'` and `'S'`.
+/// Use a [`DmsFormat`](struct.DmsFormat.html) for control over the precision.
+///
+///```rust
+/// extern crate proj;
+/// use proj::radians_to_dms;
+///
+/// let lon = radians_to_dms((-122.5f64).to_radians(), 'E', 'W');
+/// assert_eq!(lon, "122d30'W");
+/// ```
+///
+/// # Safety
+/// This method contains unsafe code.
+pub fn radians_to_dms(angle: f64, positive: char, negative: char) -> String {
+    let mut buf = [0 as c_char; 64];
+    unsafe {
+        proj_rtodms(
+            buf.as_mut_ptr(),
+            angle,
+            positive as c_int,
+            negative as c_int,
+        );
+        CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned()
+    }
+}
+
+/// A DMS angle formatter with configurable precision and hemisphere letters
+///
+/// The output follows `PROJ`'s layout: e.g. `47d30'15.2"N`, with trailing zeros and
+/// zero minutes and seconds omitted.
+///
+///```rust
+/// extern crate proj;
+/// use proj::DmsFormat;
+///
+/// let format = DmsFormat::new().precision(1).hemispheres('N', 'S');
+/// assert_eq!(format.format_degrees(47.504222222), "47d30'15.2\"N");
+/// assert_eq!(format.format_degrees(-33.5), "33d30'S");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DmsFormat {
+    precision: usize,
+    hemispheres: Option<(char, char)>,
+}
+
+impl Default for DmsFormat {
+    fn default() -> Self {
+        DmsFormat {
+            precision: 3,
+            hemispheres: None,
+        }
+    }
+}
+
+impl DmsFormat {
+    /// Create a formatter giving seconds to three decimal places, with negative angles prefixed by `-`
+    pub fn new() -> Self {
+        DmsFormat::default()
+    }
+
+    /// Set the maximum number of decimal places of the seconds
+    pub fn precision(mut self, digits: usize) -> Self {
+        self.precision = digits;
+        self
+    }
+
+    /// Append `positive` or `negative` to angles, instead of prefixing negative angles with `-`
+    pub fn hemispheres(mut self, positive: char, negative: char) -> Self {
+        self.hemispheres = Some((positive, negative));
+        self
+    }
+
+    /// Format an angle in radians
+    pub fn format_radians(&self, angle: f64) -> String {
+        self.format_degrees(angle.to_degrees())
+    }
+
+    /// Format an angle in degrees
+    pub fn format_degrees(&self, angle: f64) -> String {
+        let (prefix, suffix) = match self.hemispheres {
+            Some((positive, negative)) => (
+                "",
+                if angle < 0.0 { negative } else { positive }.to_string(),
+            ),
+            None => (if angle < 0.0 { "-" } else { "" }, String::new()),
+        };
+        // round to the requested precision before splitting, so seconds never round up to 60
+        let scale = 10f64.powi(self.precision as i32);
+        let total = (angle.abs() * 3600.0 * scale).round();
+        let seconds = (total % (60.0 * scale)) / scale;
+        let minutes = (total / (60.0 * scale)).floor();
+        let degrees = (minutes / 60.0).floor();
+        let minutes = minutes % 60.0;
+        if seconds != 0.0 {
+            let seconds = format!("{:.*}", self.precision, seconds);
+            let seconds = if seconds.contains('.') {
+                seconds.trim_end_matches('0').trim_end_matches('.')
+            } else {
+                &seconds
+            };
+            format!("{}{}d{}'{}\"{}", prefix, degrees, minutes, seconds, suffix)
+        } else if minutes != 0.0 {
+            format!("{}{}d{}'{}", prefix, degrees, minutes, suffix)
+        } else {
+            format!("{}{}d{}", prefix, degrees, suffix)
+        }
+    }
+
+    /// Format a `Point` in radians as DMS longitude and latitude, using `E`/`W` and `N`/`S` hemispheres
+    pub fn format_point(&self, point: Point<f64>) -> (String, String) {
+        (
+            self.hemispheres('E', 'W').format_radians(point.x()),
+            self.hemispheres('N', 'S').format_radians(point.y()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{dms_point, dms_to_degrees, dms_to_radians, radians_to_dms, DmsFormat};
+
+    fn assert_almost_eq(a: f64, b: f64) {
+        let f: f64 = a / b;
+        assert!(f < 1.00001);
+        assert!(f > 0.99999);
+    }
+
+    #[test]
+    fn test_parse_dms() {
+        assert_almost_eq(dms_to_degrees("47d30'15.2\"N").unwrap(), 47.504222222);
+        assert_almost_eq(dms_to_degrees("47°30'15.2\"N").unwrap(), 47.504222222);
+        assert_almost_eq(dms_to_degrees("122d19'W").unwrap(), -122.316666667);
+        assert_almost_eq(dms_to_degrees("-12.5").unwrap(), -12.5);
+        assert_almost_eq(dms_to_radians("90dN").unwrap(), std::f64::consts::FRAC_PI_2);
+    }
+
+    #[test]
+    fn test_parse_dms_invalid() {
+        assert!(dms_to_radians("").is_err());
+        assert!(dms_to_radians("north").is_err());
+        assert!(dms_to_radians("47d30'N and more").is_err());
+    }
+
+    #[test]
+    fn test_dms_point() {
+        let point = dms_point("2d20'14.025\"E", "48d50'N").unwrap();
+        assert_almost_eq(point.x().to_degrees(), 2.33722917);
+        assert_almost_eq(point.y().to_degrees(), 48.833333333);
+    }
+
+    #[test]
+    fn test_proj_format() {
+        assert_eq!(
+            radians_to_dms(47.504222222f64.to_radians(), 'N', 'S'),
+            "47d30'15.2\"N"
+        );
+        assert_eq!(radians_to_dms(0.0, 'E', 'W'), "0dE");
+    }
+
+    #[test]
+    fn test_format() {
+        let format = DmsFormat::new();
+        assert_eq!(format.format_degrees(-122.316666667), "-122d19'");
+        assert_eq!(format.format_degrees(-122.3169), "-122d19'0.84\"");
+        let format = format.precision(0);
+        assert_eq!(format.format_degrees(-122.3169), "-122d19'1\"");
+        assert_eq!(format.format_degrees(10.0), "10d");
+        // seconds which round up carry into the minutes
+        assert_eq!(format.format_degrees(10.0 + 59.9 / 3600.0), "10d1'");
+        let format = DmsFormat::new().precision(2).hemispheres('E', 'W');
+        assert_eq!(format.format_degrees(-0.5), "0d30'W");
+    }
+
+    #[test]
+    fn test_format_point_roundtrip() {
+        let point = dms_point("2d20'14.025\"E", "48d50'10.5\"S").unwrap();
+        let (lon, lat) = DmsFormat::new().format_point(point);
+        assert_eq!(lon, "2d20'14.025\"E");
+        assert_eq!(lat, "48d50'10.5\"S");
+    }
+}
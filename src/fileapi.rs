@@ -0,0 +1,409 @@
+use crate::proj::{context_error, ProjBuilder, ProjError};
+use libc::{c_char, c_int, c_longlong, c_ulonglong, c_void, size_t, SEEK_CUR, SEEK_END, SEEK_SET};
+use proj_sys::{
+    proj_context_set_fileapi, PJ_CONTEXT, PROJ_FILE_API, PROJ_FILE_HANDLE, PROJ_OPEN_ACCESS,
+    PROJ_OPEN_ACCESS_PROJ_OPEN_ACCESS_CREATE, PROJ_OPEN_ACCESS_PROJ_OPEN_ACCESS_READ_UPDATE,
+};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+use std::slice;
+use std::sync::Arc;
+
+/// The access mode requested when `PROJ` opens a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenAccess {
+    /// Read-only access. The file must exist.
+    ReadOnly,
+    /// Read and write access. The file must exist.
+    ReadUpdate,
+    /// Read and write access. The file is created if needed, and truncated if it exists.
+    Create,
+}
+
+impl From<PROJ_OPEN_ACCESS> for OpenAccess {
+    fn from(access: PROJ_OPEN_ACCESS) -> Self {
+        #[allow(non_upper_case_globals)]
+        match access {
+            PROJ_OPEN_ACCESS_PROJ_OPEN_ACCESS_READ_UPDATE => OpenAccess::ReadUpdate,
+            PROJ_OPEN_ACCESS_PROJ_OPEN_ACCESS_CREATE => OpenAccess::Create,
+            _ => OpenAccess::ReadOnly,
+        }
+    }
+}
+
+/// An open file, as handed to `PROJ` by a [`FileApi`](trait.FileApi.html)
+///
+/// This is implemented for any `Read + Seek` source, in which case writing is unsupported.
+pub trait FileHandle: Send {
+    /// Read up to `buf.len()` bytes, returning the number of bytes read
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+    /// Write up to `buf.len()` bytes, returning the number of bytes written
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize>;
+    /// Seek to a position, returning the new offset from the start of the file
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64>;
+}
+
+impl<T: Read + Seek + Send> FileHandle for T {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "file is read-only",
+        ))
+    }
+
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        Seek::seek(self, pos)
+    }
+}
+
+/// Storage used by `PROJ` in place of the file system
+///
+/// Once installed using [`ProjBuilder::set_file_api()`](struct.ProjBuilder.html#method.set_file_api),
+/// all resource files opened by `PROJ` (e.g. `proj.db`, init files and grids) are served
+/// by this implementation. `PROJ` still looks for files in its search paths, so these will
+/// usually need to be set using
+/// [`ProjBuilder::set_search_paths()`](struct.ProjBuilder.html#method.set_search_paths) too.
+///
+/// Only `open` and `exists` are required; the remaining methods are used by `PROJ`
+/// when writing to its grid cache, and fail by default.
+pub trait FileApi: Send {
+    /// Open the file at `path`, returning `None` if it cannot be opened using the requested access mode
+    fn open(&self, path: &str, access: OpenAccess) -> Option<Box<dyn FileHandle>>;
+    /// Whether a file exists at `path`
+    fn exists(&self, path: &str) -> bool;
+    /// Create a directory, returning `true` on success
+    fn mkdir(&self, _path: &str) -> bool {
+        false
+    }
+    /// Remove a file, returning `true` on success
+    fn unlink(&self, _path: &str) -> bool {
+        false
+    }
+    /// Rename a file, returning `true` on success
+    fn rename(&self, _old_path: &str, _new_path: &str) -> bool {
+        false
+    }
+}
+
+// Shared, immutable file contents
+#[derive(Clone)]
+struct SharedBytes(Arc<Cow<'static, [u8]>>);
+
+impl AsRef<[u8]> for SharedBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// A read-only [`FileApi`](trait.FileApi.html) serving files from memory
+///
+/// Contents may be owned (e.g. read from an object store), or embedded in the binary
+/// using `include_bytes!`.
+///
+///```rust
+/// extern crate proj;
+/// use proj::{MemoryFileApi, ProjBuilder};
+///
+/// let mut files = MemoryFileApi::new();
+/// files.insert("/memory/site", &b"<site> +proj=tmerc +lat_0=51 +lon_0=-1.5 +ellps=GRS80 <>\n"[..]);
+///
+/// let mut builder = ProjBuilder::new();
+/// builder.set_search_paths(&["/memory"]);
+/// builder.set_file_api(files).unwrap();
+/// let site = builder.proj("+init=site:site").unwrap();
+/// ```
+#[derive(Default, Clone)]
+pub struct MemoryFileApi {
+    files: HashMap<String, SharedBytes>,
+}
+
+impl MemoryFileApi {
+    /// Create an empty file store
+    pub fn new() -> Self {
+        MemoryFileApi::default()
+    }
+
+    /// Add a file at `path`, replacing any existing file
+    pub fn insert<P, B>(&mut self, path: P, contents: B)
+    where
+        P: Into<String>,
+        B: Into<Cow<'static, [u8]>>,
+    {
+        self.files
+            .insert(path.into(), SharedBytes(Arc::new(contents.into())));
+    }
+
+    /// Remove the file at `path`, returning `true` if it was present
+    pub fn remove(&mut self, path: &str) -> bool {
+        self.files.remove(path).is_some()
+    }
+}
+
+impl FileApi for MemoryFileApi {
+    fn open(&self, path: &str, access: OpenAccess) -> Option<Box<dyn FileHandle>> {
+        if access != OpenAccess::ReadOnly {
+            return None;
+        }
+        self.files
+            .get(path)
+            .map(|contents| Box::new(Cursor::new(contents.clone())) as Box<dyn FileHandle>)
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.files.contains_key(path)
+    }
+}
+
+/// The user data passed to the file API callbacks
+pub(crate) struct FileApiHandler {
+    api: Box<dyn FileApi>,
+}
+
+impl ProjBuilder {
+    /// Serve all files opened by `PROJ` using `api`, instead of the file system
+    ///
+    /// See [`FileApi`](trait.FileApi.html) for details.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn set_file_api<F: FileApi + 'static>(&mut self, api: F) -> Result<(), ProjError> {
+        let handler = Box::new(FileApiHandler { api: Box::new(api) });
+        let user_data = &*handler as *const FileApiHandler as *mut c_void;
+        let callbacks = PROJ_FILE_API {
+            version: 1,
+            open_cbk: Some(open_cbk),
+            read_cbk: Some(read_cbk),
+            write_cbk: Some(write_cbk),
+            seek_cbk: Some(seek_cbk),
+            tell_cbk: Some(tell_cbk),
+            close_cbk: Some(close_cbk),
+            exists_cbk: Some(exists_cbk),
+            mkdir_cbk: Some(mkdir_cbk),
+            unlink_cbk: Some(unlink_cbk),
+            rename_cbk: Some(rename_cbk),
+        };
+        if unsafe { proj_context_set_fileapi(self.ctx, &callbacks, user_data) } == 0 {
+            return Err(ProjError::Context(context_error(self.ctx)));
+        }
+        // the previous handler (if any) is no longer referenced by the context
+        self.callbacks.file_api = Some(handler);
+        Ok(())
+    }
+}
+
+unsafe fn handler<'a>(user_data: *mut c_void) -> &'a FileApiHandler {
+    &*(user_data as *const FileApiHandler)
+}
+
+unsafe fn file<'a>(handle: *mut PROJ_FILE_HANDLE) -> &'a mut Box<dyn FileHandle> {
+    &mut *(handle as *mut Box<dyn FileHandle>)
+}
+
+unsafe fn path<'a>(filename: *const c_char) -> Option<&'a str> {
+    CStr::from_ptr(filename).to_str().ok()
+}
+
+unsafe fn with_path<F: FnOnce(&str) -> bool>(filename: *const c_char, f: F) -> c_int {
+    match path(filename) {
+        Some(path) => f(path) as c_int,
+        None => 0,
+    }
+}
+
+unsafe extern "C" fn open_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    filename: *const c_char,
+    access: PROJ_OPEN_ACCESS,
+    user_data: *mut c_void,
+) -> *mut PROJ_FILE_HANDLE {
+    path(filename)
+        .and_then(|path| handler(user_data).api.open(path, access.into()))
+        .map_or(std::ptr::null_mut(), |file| {
+            Box::into_raw(Box::new(file)) as *mut PROJ_FILE_HANDLE
+        })
+}
+
+unsafe extern "C" fn read_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    handle: *mut PROJ_FILE_HANDLE,
+    buffer: *mut c_void,
+    size: size_t,
+    _user_data: *mut c_void,
+) -> size_t {
+    // a slice can't be built from a null pointer, even an empty one
+    if buffer.is_null() || size == 0 {
+        return 0;
+    }
+    let buf = slice::from_raw_parts_mut(buffer as *mut u8, size);
+    let file = file(handle);
+    // like fread, only return a short count at the end of the file or on error
+    let mut total = 0;
+    while total < size {
+        match file.read(&mut buf[total..]) {
+            Ok(0) => break,
+            Ok(n) => total += n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        }
+    }
+    total
+}
+
+unsafe extern "C" fn write_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    handle: *mut PROJ_FILE_HANDLE,
+    buffer: *const c_void,
+    size: size_t,
+    _user_data: *mut c_void,
+) -> size_t {
+    // a slice can't be built from a null pointer, even an empty one
+    if buffer.is_null() || size == 0 {
+        return 0;
+    }
+    let buf = slice::from_raw_parts(buffer as *const u8, size);
+    let file = file(handle);
+    let mut total = 0;
+    while total < size {
+        match file.write(&buf[total..]) {
+            Ok(0) => break,
+            Ok(n) => total += n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        }
+    }
+    total
+}
+
+unsafe extern "C" fn seek_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    handle: *mut PROJ_FILE_HANDLE,
+    offset: c_longlong,
+    whence: c_int,
+    _user_data: *mut c_void,
+) -> c_int {
+    let pos = match whence {
+        SEEK_SET if offset >= 0 => SeekFrom::Start(offset as u64),
+        SEEK_CUR => SeekFrom::Current(offset),
+        SEEK_END => SeekFrom::End(offset),
+        _ => return 0,
+    };
+    file(handle).seek(pos).is_ok() as c_int
+}
+
+unsafe extern "C" fn tell_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    handle: *mut PROJ_FILE_HANDLE,
+    _user_data: *mut c_void,
+) -> c_ulonglong {
+    file(handle).seek(SeekFrom::Current(0)).unwrap_or(0)
+}
+
+unsafe extern "C" fn close_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    handle: *mut PROJ_FILE_HANDLE,
+    _user_data: *mut c_void,
+) {
+    drop(Box::from_raw(handle as *mut Box<dyn FileHandle>));
+}
+
+unsafe extern "C" fn exists_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    filename: *const c_char,
+    user_data: *mut c_void,
+) -> c_int {
+    with_path(filename, |path| handler(user_data).api.exists(path))
+}
+
+unsafe extern "C" fn mkdir_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    filename: *const c_char,
+    user_data: *mut c_void,
+) -> c_int {
+    with_path(filename, |path| handler(user_data).api.mkdir(path))
+}
+
+unsafe extern "C" fn unlink_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    filename: *const c_char,
+    user_data: *mut c_void,
+) -> c_int {
+    with_path(filename, |path| handler(user_data).api.unlink(path))
+}
+
+unsafe extern "C" fn rename_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    old_path: *const c_char,
+    new_path: *const c_char,
+    user_data: *mut c_void,
+) -> c_int {
+    (match (path(old_path), path(new_path)) {
+        (Some(old_path), Some(new_path)) => handler(user_data).api.rename(old_path, new_path),
+        _ => false,
+    }) as c_int
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FileApi, MemoryFileApi, OpenAccess};
+    use crate::proj::ProjBuilder;
+    use geo_types::Point;
+    use std::io::SeekFrom;
+
+    fn assert_almost_eq(a: f64, b: f64) {
+        let f: f64 = a / b;
+        assert!(f < 1.00001);
+        assert!(f > 0.99999);
+    }
+
+    const SITE: &[u8] = b"# local grid\n<site> +proj=tmerc +lat_0=51 +lon_0=-1.5 +k=0.9996 +x_0=500000 +y_0=0 +ellps=GRS80 +units=m <>\n";
+
+    #[test]
+    fn test_memory_file_handle() {
+        let mut files = MemoryFileApi::new();
+        files.insert("/memory/site", SITE);
+        assert!(files.exists("/memory/site"));
+        assert!(!files.exists("/memory/other"));
+        assert!(files.open("/memory/site", OpenAccess::Create).is_none());
+
+        let mut file = files.open("/memory/site", OpenAccess::ReadOnly).unwrap();
+        let mut buf = [0u8; 7];
+        assert_eq!(file.read(&mut buf).unwrap(), 7);
+        assert_eq!(&buf, b"# local");
+        assert_eq!(file.seek(SeekFrom::End(-3)).unwrap(), SITE.len() as u64 - 3);
+        assert_eq!(file.read(&mut buf).unwrap(), 3);
+        assert_eq!(&buf[..3], b"<>\n");
+        assert!(file.write(b"x").is_err());
+    }
+
+    #[test]
+    fn test_init_file_from_memory() {
+        let mut files = MemoryFileApi::new();
+        files.insert("/memory/site", SITE);
+        let mut builder = ProjBuilder::new();
+        builder.set_search_paths(&["/memory"]);
+        builder.set_file_api(files).unwrap();
+        let site = builder.proj("+init=site:site").unwrap();
+        let result = site
+            .project(
+                Point::new((-1.5f64).to_radians(), 51f64.to_radians()),
+                false,
+            )
+            .unwrap();
+        assert_almost_eq(result.x(), 500000.0);
+        assert!(result.y().abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_missing_file_in_memory() {
+        let mut builder = ProjBuilder::new();
+        builder.set_search_paths(&["/memory"]);
+        builder.set_file_api(MemoryFileApi::new()).unwrap();
+        assert!(builder.proj("+init=site:site").is_none());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::crs::Ellipsoid;
+use crate::pipeline::{Pipeline, Step};
+use crate::proj::Proj;
+
+/// The origin of a topocentric (local East, North, Up) coordinate system
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TopocentricOrigin {
+    /// Geodetic coordinates of the origin: longitude and latitude in degrees, and ellipsoidal height in metres
+    Geodetic { lon: f64, lat: f64, h: f64 },
+    /// Geocentric cartesian coordinates of the origin, in metres
+    Geocentric { x: f64, y: f64, z: f64 },
+}
+
+/// Conversion from geodetic to geocentric cartesian coordinates on an ellipsoid
+fn geodetic_to_geocentric(ellipsoid: &Ellipsoid) -> Pipeline {
+    Pipeline::new().step(Step::unitconvert("deg", "rad")).step(
+        Step::new("cart")
+            .param("a", ellipsoid.semi_major)
+            .param("b", ellipsoid.semi_minor()),
+    )
+}
+
+/// A Helmert rotation of the coordinate frame by `angle` degrees about a single axis (`"rx"` or `"rz"`)
+fn rotation(axis: &str, angle: f64) -> Step {
+    Step::new("helmert")
+        .param(axis, angle * 3600.0)
+        .param("convention", "coordinate_frame")
+        .flag("exact")
+}
+
+/// Append steps converting geocentric cartesian coordinates to East, North, Up relative to `origin` to `pipeline`
+///
+/// The `topocentric` operation is only available from `PROJ` 8, so the origin is moved to `(0, 0, 0)`, and the
+/// frame is then rotated about the Z axis so that X points East, and about the new X axis so that Z points Up.
+fn topocentric(
+    ellipsoid: &Ellipsoid,
+    origin: TopocentricOrigin,
+    pipeline: Pipeline,
+) -> Option<Pipeline> {
+    let ecef = Proj::new_geocentric(ellipsoid)?;
+    let (x, y, z, lon, lat) = match origin {
+        TopocentricOrigin::Geodetic { lon, lat, h } => {
+            let (x, y, z) = ecef.project_3d((lon, lat, h), false).ok()?;
+            (x, y, z, lon, lat)
+        }
+        TopocentricOrigin::Geocentric { x, y, z } => {
+            let (lon, lat, _) = ecef.project_3d((x, y, z), true).ok()?;
+            (x, y, z, lon, lat)
+        }
+    };
+    Some(
+        pipeline
+            .step(
+                Step::new("helmert")
+                    .param("x", -x)
+                    .param("y", -y)
+                    .param("z", -z),
+            )
+            .step(rotation("rz", lon + 90.0))
+            .step(rotation("rx", 90.0 - lat)),
+    )
+}
+
+impl Proj {
+    /// Create a conversion from geodetic to geocentric cartesian (ECEF) coordinates on `ellipsoid`
+    ///
+    /// Input coordinates are `(longitude, latitude, height)`, in degrees and metres, and output coordinates
+    /// are `(X, Y, Z)`, in metres. Use [`project_3d`](#method.project_3d) or
+    /// [`project_array_3d`](#method.project_array_3d) to carry out the conversion or its inverse.
+    /// The ellipsoid of a geographic CRS can be obtained using [`ellipsoid`](#method.ellipsoid).
+    ///
+    /// ```rust
+    /// use proj::Proj;
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// let wgs84 = Proj::new("EPSG:4326").unwrap();
+    /// let ecef = Proj::new_geocentric(&wgs84.ellipsoid().unwrap()).unwrap();
+    /// let (x, y, z) = ecef.project_3d((0.0f64, 0.0f64, 100.0f64), false).unwrap();
+    /// assert_approx_eq!(x, 6378237.0f64, 1.0e-6);
+    /// let (_, _, h) = ecef.project_3d((x, y, z), true).unwrap();
+    /// assert_approx_eq!(h, 100.0f64, 1.0e-6);
+    /// ```
+    pub fn new_geocentric(ellipsoid: &Ellipsoid) -> Option<Proj> {
+        Proj::new(&geodetic_to_geocentric(ellipsoid).to_string())
+    }
+
+    /// Create a conversion from geocentric cartesian (ECEF) coordinates to topocentric (local East, North, Up)
+    /// coordinates on `ellipsoid`, relative to `origin`
+    ///
+    /// Input and output coordinates are in metres. The conversion is built from Helmert steps, so it doesn't
+    /// require the `topocentric` operation of `PROJ` 8.
+    pub fn new_geocentric_to_topocentric(
+        ellipsoid: &Ellipsoid,
+        origin: TopocentricOrigin,
+    ) -> Option<Proj> {
+        Proj::new(&topocentric(ellipsoid, origin, Pipeline::new())?.to_string())
+    }
+
+    /// Create a conversion from geodetic to topocentric (local East, North, Up) coordinates on `ellipsoid`,
+    /// relative to `origin`
+    ///
+    /// Input coordinates are `(longitude, latitude, height)`, in degrees and metres, and output coordinates
+    /// are `(East, North, Up)`, in metres.
+    ///
+    /// ```rust
+    /// use proj::{Ellipsoid, Proj, TopocentricOrigin};
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// let origin = TopocentricOrigin::Geodetic {
+    ///     lon: 5.0,
+    ///     lat: 55.0,
+    ///     h: 200.0,
+    /// };
+    /// let enu = Proj::new_topocentric(&Ellipsoid::grs80(), origin).unwrap();
+    /// let (e, n, u) = enu.project_3d((5.0f64, 55.0f64, 300.0f64), false).unwrap();
+    /// assert_approx_eq!(e, 0.0f64, 1.0e-6);
+    /// assert_approx_eq!(n, 0.0f64, 1.0e-6);
+    /// assert_approx_eq!(u, 100.0f64, 1.0e-6);
+    /// ```
+    pub fn new_topocentric(ellipsoid: &Ellipsoid, origin: TopocentricOrigin) -> Option<Proj> {
+        Proj::new(&topocentric(ellipsoid, origin, geodetic_to_geocentric(ellipsoid))?.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Ellipsoid, Proj, TopocentricOrigin};
+
+    fn assert_almost_eq(a: f64, b: f64) {
+        let f: f64 = a / b;
+        assert!(f < 1.00001);
+        assert!(f > 0.99999);
+    }
+
+    #[test]
+    fn test_geocentric() {
+        let ecef = Proj::new_geocentric(&Ellipsoid::wgs84()).unwrap();
+        let mut v = vec![(0.0, 90.0, 0.0), (90.0, 0.0, 0.0)];
+        ecef.project_array_3d(&mut v, false).unwrap();
+        assert_almost_eq(v[0].2, 6356752.314245179);
+        assert_almost_eq(v[1].1, 6378137.0);
+        ecef.project_array_3d(&mut v, true).unwrap();
+        assert_almost_eq(v[0].1, 90.0);
+        assert_almost_eq(v[1].0, 90.0);
+    }
+
+    #[test]
+    fn test_topocentric() {
+        let ellipsoid = Ellipsoid::grs80();
+        let origin = TopocentricOrigin::Geodetic {
+            lon: 5.0,
+            lat: 55.0,
+            h: 200.0,
+        };
+        let ecef = Proj::new_geocentric(&ellipsoid).unwrap();
+        let (x, y, z) = ecef.project_3d((5.0, 55.0, 200.0), false).unwrap();
+        let enu = Proj::new_geocentric_to_topocentric(
+            &ellipsoid,
+            TopocentricOrigin::Geocentric { x, y, z },
+        )
+        .unwrap();
+        // the origin itself
+        let t = enu.project_3d((x, y, z), false).unwrap();
+        assert!(t.0.abs() < 1e-6 && t.1.abs() < 1e-6 && t.2.abs() < 1e-6);
+        // a point to the north-east of the origin
+        let geodetic_enu = Proj::new_topocentric(&ellipsoid, origin).unwrap();
+        let t = geodetic_enu
+            .project_3d((5.001, 55.001, 200.0), false)
+            .unwrap();
+        assert!(t.0 > 0.0 && t.1 > 0.0);
+        // a point due east of the origin, along its parallel
+        let t = geodetic_enu
+            .project_3d((5.001f64, 55.0f64, 200.0f64), false)
+            .unwrap();
+        assert!(t.0 > 63.9 && t.0 < 64.1);
+        assert!(t.1.abs() < 0.01 && t.2 < 0.0);
+        let back = geodetic_enu.project_3d(t, true).unwrap();
+        assert_almost_eq(back.0, 5.001);
+        assert_almost_eq(back.1, 55.001);
+    }
+}
@@ -0,0 +1,367 @@
+use crate::proj::{Proj, ProjError};
+use geo_types::{LineString, Point, Polygon};
+use libc::c_double;
+use num_traits::Float;
+use proj_sys::{
+    proj_errno, proj_errno_reset, proj_geod, proj_lp_dist, proj_lpz_dist, PJconsts, PJ_COORD,
+    PJ_LP, PJ_LPZ,
+};
+
+// Maximum length (in metres) of the segments used to approximate geodesic polygon edges
+// when calculating areas
+const DENSIFY_STEP: f64 = 10_000.0;
+
+// PROJ parameters which define an ellipsoid, as found in a definition string
+const ELLIPSOID_PARAMS: [&str; 7] = ["ellps", "a", "b", "rf", "f", "R", "es"];
+
+/// The solution of the inverse geodesic problem between two points
+///
+/// Azimuths are in radians, clockwise from north.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeodesicInverse {
+    /// The length of the geodesic, in metres
+    pub distance: f64,
+    /// The forward azimuth of the geodesic at the first point
+    pub azimuth_start: f64,
+    /// The forward azimuth of the geodesic at the second point
+    pub azimuth_end: f64,
+}
+
+/// Geodesic calculations on the ellipsoid of a `Proj` instance
+///
+/// All input coordinates are geodetic (in radians), and all distances and areas
+/// are on the surface of the ellipsoid, in metres and square metres respectively.
+///
+/// ```rust
+/// use proj::Proj;
+/// extern crate geo_types;
+/// use geo_types::Point;
+/// # use assert_approx_eq::assert_approx_eq;
+/// let wgs84 = Proj::new("EPSG:4326").unwrap();
+/// let geod = wgs84.geod().unwrap();
+/// // one degree of longitude along the equator
+/// let d = geod
+///     .distance(Point::new(0.0f64, 0.0), Point::new(1.0f64.to_radians(), 0.0))
+///     .unwrap();
+/// assert_approx_eq!(d, 111319.49079327357f64, 1.0e-6);
+/// ```
+pub struct Geod {
+    longlat: Proj,
+    ellipsoid: String,
+}
+
+impl Proj {
+    /// Create a [`Geod`](struct.Geod.html) instance for geodesic calculations on the ellipsoid of this object
+    ///
+    /// The ellipsoid is taken from [`ellipsoid`](#method.ellipsoid) if available, or from the
+    /// ellipsoid parameters of a single-step PROJ definition.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn geod(&self) -> Result<Geod, ProjError> {
+        let ellipsoid = self
+            .ellipsoid()
+            .map(|ellipsoid| ellipsoid.proj_params())
+            .or_else(|| definition_ellipsoid(&self.def()))
+            .ok_or_else(|| {
+                ProjError::Geodesic("the ellipsoid could not be determined".to_string())
+            })?;
+        let longlat = Proj::new(&format!("+proj=longlat {}", ellipsoid)).ok_or_else(|| {
+            ProjError::Geodesic(format!("invalid ellipsoid definition: {}", ellipsoid))
+        })?;
+        Ok(Geod { longlat, ellipsoid })
+    }
+}
+
+/// Extract the ellipsoid parameters of a single-step PROJ definition
+fn definition_ellipsoid(definition: &str) -> Option<String> {
+    let tokens = definition.split_whitespace().collect::<Vec<_>>();
+    if tokens.contains(&"proj=pipeline") {
+        return None;
+    }
+    let params = tokens
+        .iter()
+        .filter(|t| {
+            let key = t.split('=').next().unwrap_or("");
+            ELLIPSOID_PARAMS.contains(&key)
+        })
+        .map(|t| format!("+{}", t))
+        .collect::<Vec<_>>();
+    if params.is_empty() {
+        None
+    } else {
+        Some(params.join(" "))
+    }
+}
+
+fn lp<U: Float>(point: Point<U>) -> PJ_COORD {
+    PJ_COORD {
+        lp: PJ_LP {
+            lam: point.x().to_f64().unwrap(),
+            phi: point.y().to_f64().unwrap(),
+        },
+    }
+}
+
+fn lpz<U: Float>(point: (U, U, U)) -> PJ_COORD {
+    PJ_COORD {
+        lpz: PJ_LPZ {
+            lam: point.0.to_f64().unwrap(),
+            phi: point.1.to_f64().unwrap(),
+            z: point.2.to_f64().unwrap(),
+        },
+    }
+}
+
+impl Geod {
+    fn c_proj(&self) -> *mut PJconsts {
+        self.longlat.c_proj
+    }
+
+    /// Check the PROJ error state after a geodesic calculation
+    fn check<V>(&self, value: V) -> Result<V, ProjError> {
+        let err = unsafe { proj_errno(self.c_proj()) };
+        if err == 0 {
+            Ok(value)
+        } else {
+            Err(ProjError::Geodesic(crate::proj::error_message(err)))
+        }
+    }
+
+    /// Solve the inverse geodesic problem: the distance and azimuths between two points
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn inverse<T, U>(&self, a: T, b: T) -> Result<GeodesicInverse, ProjError>
+    where
+        T: Into<Point<U>>,
+        U: Float,
+    {
+        let geod = unsafe {
+            proj_errno_reset(self.c_proj());
+            proj_geod(self.c_proj(), lp(a.into()), lp(b.into()))
+        };
+        // PROJ returns azimuths in degrees
+        let v = unsafe { geod.v };
+        self.check(GeodesicInverse {
+            distance: v[0],
+            azimuth_start: v[1].to_radians(),
+            azimuth_end: v[2].to_radians(),
+        })
+    }
+
+    /// Solve the direct geodesic problem: the point reached by travelling `distance` metres
+    /// from `start` with an initial `azimuth` (in radians, clockwise from north)
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn direct<T, U>(&self, start: T, azimuth: U, distance: U) -> Result<Point<U>, ProjError>
+    where
+        T: Into<Point<U>>,
+        U: Float,
+    {
+        let start: Point<U> = start.into();
+        let azimuth = azimuth.to_f64().unwrap();
+        let distance = distance.to_f64().unwrap();
+        // The ellipsoidal azimuthal equidistant projection preserves geodesic distances and
+        // azimuths from its centre, so its inverse solves the direct problem
+        let aeqd = self.centred("aeqd", start)?;
+        aeqd.project(
+            Point::new(
+                U::from(distance * azimuth.sin()).unwrap(),
+                U::from(distance * azimuth.cos()).unwrap(),
+            ),
+            true,
+        )
+        .map_err(|e| ProjError::Geodesic(e.to_string()))
+    }
+
+    /// The geodesic distance between two points, in metres
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn distance<T, U>(&self, a: T, b: T) -> Result<f64, ProjError>
+    where
+        T: Into<Point<U>>,
+        U: Float,
+    {
+        let dist = unsafe {
+            proj_errno_reset(self.c_proj());
+            proj_lp_dist(self.c_proj(), lp(a.into()), lp(b.into()))
+        };
+        self.check(dist)
+    }
+
+    /// The distance between two points given as `(longitude, latitude, height)`, in metres
+    ///
+    /// The geodesic distance is combined with the height difference.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn distance_3d<U>(&self, a: (U, U, U), b: (U, U, U)) -> Result<f64, ProjError>
+    where
+        U: Float,
+    {
+        let dist = unsafe {
+            proj_errno_reset(self.c_proj());
+            proj_lpz_dist(self.c_proj(), lpz(a), lpz(b))
+        };
+        self.check(dist)
+    }
+
+    /// The geodesic length of a `LineString`, in metres
+    pub fn length<U>(&self, line: &LineString<U>) -> Result<f64, ProjError>
+    where
+        U: Float,
+    {
+        line.0.windows(2).try_fold(0.0, |length, pair| {
+            Ok(length + self.distance(Point(pair[0]), Point(pair[1]))?)
+        })
+    }
+
+    /// The area enclosed by a `Polygon` whose edges are geodesics, in square metres
+    ///
+    /// The area of any interior rings is subtracted from the area of the exterior ring.
+    ///
+    /// **Note:** the area is calculated in a Lambert azimuthal equal-area projection centred on
+    /// the first vertex of the exterior ring, so it is only valid for polygons which are smaller
+    /// than a hemisphere, and don't enclose the antipode of that vertex.
+    pub fn area<U>(&self, polygon: &Polygon<U>) -> Result<f64, ProjError>
+    where
+        U: Float,
+    {
+        let exterior = polygon.exterior();
+        if exterior.0.is_empty() {
+            return Ok(0.0);
+        }
+        // The Lambert azimuthal equal-area projection preserves areas on the ellipsoid,
+        // so the area can be calculated in the plane, once edges have been densified
+        let laea = self.centred("laea", Point(exterior.0[0]))?;
+        let area = polygon
+            .interiors()
+            .iter()
+            .try_fold(self.ring_area(&laea, exterior)?, |area, ring| {
+                Ok(area - self.ring_area(&laea, ring)?)
+            })?;
+        Ok(area)
+    }
+
+    fn ring_area<U>(&self, laea: &Proj, ring: &LineString<U>) -> Result<f64, ProjError>
+    where
+        U: Float,
+    {
+        let mut points: Vec<Point<f64>> = vec![];
+        for pair in ring.0.windows(2) {
+            let (start, end) = (Point(pair[0]), Point(pair[1]));
+            let edge = self.inverse(start, end)?;
+            let segments = (edge.distance / DENSIFY_STEP).ceil().max(1.0) as usize;
+            points.push(Point::new(
+                start.x().to_f64().unwrap(),
+                start.y().to_f64().unwrap(),
+            ));
+            if segments == 1 {
+                continue;
+            }
+            // as in `direct`, but with a single projection for all the points of the edge
+            let aeqd = self.centred("aeqd", start)?;
+            let (sin, cos) = edge.azimuth_start.sin_cos();
+            let mut edge_points: Vec<Point<f64>> = (1..segments)
+                .map(|i| {
+                    let step = edge.distance * i as f64 / segments as f64;
+                    Point::new(step * sin, step * cos)
+                })
+                .collect();
+            aeqd.project_array(&mut edge_points, true)
+                .map_err(|e| ProjError::Geodesic(e.to_string()))?;
+            points.extend(edge_points);
+        }
+        laea.project_array(&mut points, false)
+            .map_err(|e| ProjError::Geodesic(e.to_string()))?;
+        let n = points.len();
+        // shoelace formula
+        let twice_area = (0..n).fold(0.0, |acc, i| {
+            let (p, q) = (points[i], points[(i + 1) % n]);
+            acc + p.x() * q.y() - q.x() * p.y()
+        });
+        Ok(twice_area.abs() / 2.0)
+    }
+
+    /// Create a projection of the given type, centred on `centre`, on this ellipsoid
+    fn centred<U: Float>(&self, projection: &str, centre: Point<U>) -> Result<Proj, ProjError> {
+        let lon_0: c_double = centre.x().to_f64().unwrap().to_degrees();
+        let lat_0: c_double = centre.y().to_f64().unwrap().to_degrees();
+        let definition = format!(
+            "+proj={} +lat_0={} +lon_0={} {}",
+            projection, lat_0, lon_0, self.ellipsoid
+        );
+        Proj::new(&definition).ok_or_else(|| {
+            ProjError::Geodesic(format!("could not create projection: {}", definition))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Proj;
+    use geo_types::{LineString, Point, Polygon};
+
+    fn assert_almost_eq(a: f64, b: f64) {
+        let f: f64 = a / b;
+        assert!(f < 1.00001);
+        assert!(f > 0.99999);
+    }
+
+    fn radians(lon: f64, lat: f64) -> Point<f64> {
+        Point::new(lon.to_radians(), lat.to_radians())
+    }
+
+    #[test]
+    fn test_inverse_and_direct() {
+        let geod = Proj::new("EPSG:4326").unwrap().geod().unwrap();
+        // one degree of longitude along the equator
+        let inv = geod.inverse(radians(0.0, 0.0), radians(1.0, 0.0)).unwrap();
+        assert_almost_eq(inv.distance, 111319.49079327358);
+        assert_almost_eq(inv.azimuth_start, 90.0f64.to_radians());
+        // JFK -> LHR and back again
+        let jfk = radians(-73.7781, 40.6413);
+        let lhr = radians(-0.4543, 51.4700);
+        let inv = geod.inverse(jfk, lhr).unwrap();
+        assert_almost_eq(inv.distance, geod.distance(jfk, lhr).unwrap());
+        let dest = geod.direct(jfk, inv.azimuth_start, inv.distance).unwrap();
+        assert_almost_eq(dest.x(), lhr.x());
+        assert_almost_eq(dest.y(), lhr.y());
+    }
+
+    #[test]
+    fn test_ellipsoid_from_definition() {
+        let osgb36 = Proj::new(
+            "+proj=tmerc +lat_0=49 +lon_0=-2 +k=0.9996012717 +x_0=400000 +y_0=-100000 +ellps=airy",
+        )
+        .unwrap();
+        let geod = osgb36.geod().unwrap();
+        let d = geod.distance(radians(0.0, 0.0), radians(1.0, 0.0)).unwrap();
+        // Airy 1830 semi-major axis
+        assert_almost_eq(d, 6377563.396 * 1.0f64.to_radians());
+    }
+
+    #[test]
+    fn test_length_and_area() {
+        let geod = Proj::new_known_crs("EPSG:4326", "EPSG:3857", None)
+            .unwrap()
+            .geod()
+            .unwrap();
+        let line = LineString::from(vec![(0.0, 0.0), (0.0, 1.0f64.to_radians())]);
+        assert_almost_eq(geod.length(&line).unwrap(), 110574.389);
+        // bounded by the equator and two meridians: 1/720th of the ellipsoid's surface
+        let lune = Polygon::new(
+            LineString::from(vec![
+                (0.0, 0.0),
+                (1.0f64.to_radians(), 0.0),
+                (0.0, 90.0f64.to_radians()),
+                (0.0, 0.0),
+            ]),
+            vec![],
+        );
+        assert_almost_eq(geod.area(&lune).unwrap(), 708424474616.7896);
+    }
+}
@@ -0,0 +1,371 @@
+use crate::proj::{Proj, ProjError};
+use crate::transform::TransformInPlace;
+use geojson::{
+    Feature, FeatureCollection, FeatureReader, FeatureWriter, GeoJson, Geometry, Position, Value,
+};
+use std::io::{BufRead, Read, Write};
+
+// Visits the positions of a GeoJSON object, and recomputes its bounding boxes
+trait Positions {
+    fn positions_mut(&mut self, f: &mut dyn FnMut(&mut Position));
+    fn update_bbox(&mut self);
+}
+
+impl Positions for Value {
+    fn positions_mut(&mut self, f: &mut dyn FnMut(&mut Position)) {
+        match self {
+            Value::Point(position) => f(position),
+            Value::MultiPoint(positions) | Value::LineString(positions) => {
+                positions.iter_mut().for_each(f)
+            }
+            Value::MultiLineString(lines) | Value::Polygon(lines) => lines
+                .iter_mut()
+                .for_each(|line| line.iter_mut().for_each(&mut *f)),
+            Value::MultiPolygon(polygons) => polygons.iter_mut().for_each(|polygon| {
+                polygon
+                    .iter_mut()
+                    .for_each(|line| line.iter_mut().for_each(&mut *f))
+            }),
+            Value::GeometryCollection(geometries) => geometries
+                .iter_mut()
+                .for_each(|geometry| geometry.positions_mut(f)),
+        }
+    }
+
+    fn update_bbox(&mut self) {
+        if let Value::GeometryCollection(geometries) = self {
+            geometries.iter_mut().for_each(Positions::update_bbox);
+        }
+    }
+}
+
+impl Positions for Geometry {
+    fn positions_mut(&mut self, f: &mut dyn FnMut(&mut Position)) {
+        self.value.positions_mut(f)
+    }
+
+    fn update_bbox(&mut self) {
+        self.value.update_bbox();
+        if let Some(bbox) = &self.bbox {
+            self.bbox = Some(compute_bbox(self, bbox.len()));
+        }
+    }
+}
+
+impl Positions for Feature {
+    fn positions_mut(&mut self, f: &mut dyn FnMut(&mut Position)) {
+        if let Some(geometry) = &mut self.geometry {
+            geometry.positions_mut(f)
+        }
+    }
+
+    fn update_bbox(&mut self) {
+        if let Some(geometry) = &mut self.geometry {
+            geometry.update_bbox();
+        }
+        if let Some(bbox) = &self.bbox {
+            self.bbox = Some(compute_bbox(self, bbox.len()));
+        }
+    }
+}
+
+impl Positions for FeatureCollection {
+    fn positions_mut(&mut self, f: &mut dyn FnMut(&mut Position)) {
+        self.features
+            .iter_mut()
+            .for_each(|feature| feature.positions_mut(f))
+    }
+
+    fn update_bbox(&mut self) {
+        self.features.iter_mut().for_each(Positions::update_bbox);
+        if let Some(bbox) = &self.bbox {
+            self.bbox = Some(compute_bbox(self, bbox.len()));
+        }
+    }
+}
+
+impl Positions for GeoJson {
+    fn positions_mut(&mut self, f: &mut dyn FnMut(&mut Position)) {
+        match self {
+            GeoJson::Geometry(geometry) => geometry.positions_mut(f),
+            GeoJson::Feature(feature) => feature.positions_mut(f),
+            GeoJson::FeatureCollection(collection) => collection.positions_mut(f),
+        }
+    }
+
+    fn update_bbox(&mut self) {
+        match self {
+            GeoJson::Geometry(geometry) => geometry.update_bbox(),
+            GeoJson::Feature(feature) => feature.update_bbox(),
+            GeoJson::FeatureCollection(collection) => collection.update_bbox(),
+        }
+    }
+}
+
+/// Compute the bounding box of an object's positions, keeping the dimension of the existing one
+///
+/// A 3D bounding box (of length 6) is kept if any position has a z coordinate.
+fn compute_bbox<G: Positions>(object: &mut G, len: usize) -> Vec<f64> {
+    let mut min = [f64::INFINITY; 3];
+    let mut max = [f64::NEG_INFINITY; 3];
+    object.positions_mut(&mut |position| {
+        for (i, &ordinate) in position.iter().take(3).enumerate() {
+            min[i] = min[i].min(ordinate);
+            max[i] = max[i].max(ordinate);
+        }
+    });
+    if len >= 6 && min[2] <= max[2] {
+        vec![min[0], min[1], min[2], max[0], max[1], max[2]]
+    } else {
+        vec![min[0], min[1], max[0], max[1]]
+    }
+}
+
+/// Transform all the positions of a GeoJSON object as a single batch, and recompute its bounding boxes
+fn transform_positions<G: Positions>(
+    object: &mut G,
+    proj: &Proj,
+    inverse: bool,
+) -> Result<(), ProjError> {
+    let mut points = vec![];
+    let mut invalid = false;
+    object.positions_mut(&mut |position| match position[..] {
+        [x, y] => points.push([x, y, 0.0]),
+        [x, y, z, ..] => points.push([x, y, z]),
+        _ => invalid = true,
+    });
+    if invalid {
+        return Err(ProjError::GeoJson(
+            "positions must have at least two coordinates".to_string(),
+        ));
+    }
+    proj.project_array(&mut points, inverse)?;
+    let mut transformed = points.into_iter();
+    object.positions_mut(&mut |position| {
+        if let Some(point) = transformed.next() {
+            // any further ordinates (e.g. a measure) are left unchanged
+            let dimensions = position.len().min(3);
+            position[..dimensions].copy_from_slice(&point[..dimensions]);
+        }
+    });
+    object.update_bbox();
+    Ok(())
+}
+
+macro_rules! impl_transform {
+    ($($object:ident),*) => {
+        $(
+            impl TransformInPlace<f64> for $object {
+                fn transform_in_place(&mut self, proj: &Proj) -> Result<(), ProjError> {
+                    transform_positions(self, proj, false)
+                }
+
+                fn transform_inverse_in_place(&mut self, proj: &Proj) -> Result<(), ProjError> {
+                    transform_positions(self, proj, true)
+                }
+            }
+        )*
+    };
+}
+
+impl_transform!(Value, Geometry, Feature, FeatureCollection, GeoJson);
+
+/// Reproject the features of a GeoJSON `FeatureCollection` document one at a time,
+/// without loading the whole document into memory
+///
+/// The features read from `reader` are transformed in the forward direction using `proj`, and
+/// written to `writer` as a new `FeatureCollection`. Feature properties and foreign members are
+/// preserved, and bounding boxes are recomputed, but the bounding box and foreign members of
+/// the collection itself are dropped. Returns the number of features written.
+///
+/// See [`TransformInPlace`](trait.TransformInPlace.html) for reprojecting documents held in memory.
+///
+///```rust
+/// extern crate proj;
+/// use proj::{reproject_features, Proj};
+///
+/// let ft_to_m = Proj::new_known_crs("EPSG:2230", "EPSG:26946", None).unwrap();
+/// let input = r#"{
+///     "type": "FeatureCollection",
+///     "features": [{
+///         "type": "Feature",
+///         "geometry": {"type": "Point", "coordinates": [4760096.421921, 3744293.729449]},
+///         "properties": {"name": "Oceanside"}
+///     }]
+/// }"#;
+/// let mut output = vec![];
+/// let count = reproject_features(&ft_to_m, input.as_bytes(), &mut output).unwrap();
+/// assert_eq!(count, 1);
+/// assert!(String::from_utf8(output).unwrap().contains("1450880.29"));
+/// ```
+pub fn reproject_features<R: Read, W: Write>(
+    proj: &Proj,
+    reader: R,
+    writer: W,
+) -> Result<usize, ProjError> {
+    let mut writer = FeatureWriter::from_writer(writer);
+    let mut count = 0;
+    for feature in FeatureReader::from_reader(reader).features() {
+        let mut feature = feature.map_err(|err| ProjError::GeoJson(err.to_string()))?;
+        feature.transform_in_place(proj)?;
+        writer
+            .write_feature(&feature)
+            .map_err(|err| ProjError::GeoJson(err.to_string()))?;
+        count += 1;
+    }
+    writer
+        .finish()
+        .map_err(|err| ProjError::GeoJson(err.to_string()))?;
+    Ok(count)
+}
+
+/// Reproject a newline-delimited sequence of GeoJSON objects one line at a time
+///
+/// Each non-empty line read from `reader` is a GeoJSON object, optionally prefixed by an
+/// ASCII record separator as in [RFC 8142](https://tools.ietf.org/html/rfc8142). It is transformed in the
+/// forward direction using `proj`, and written to `writer` on a line of its own.
+/// Returns the number of objects written.
+pub fn reproject_geojson_lines<R: BufRead, W: Write>(
+    proj: &Proj,
+    reader: R,
+    mut writer: W,
+) -> Result<usize, ProjError> {
+    let mut count = 0;
+    for line in reader.lines() {
+        let line = line.map_err(|err| ProjError::GeoJson(err.to_string()))?;
+        let line = line.trim_start_matches('\u{1e}').trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut geojson: GeoJson = line
+            .parse()
+            .map_err(|err: geojson::Error| ProjError::GeoJson(err.to_string()))?;
+        geojson.transform_in_place(proj)?;
+        writeln!(writer, "{}", geojson).map_err(|err| ProjError::GeoJson(err.to_string()))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{reproject_features, reproject_geojson_lines};
+    use crate::proj::Proj;
+    use crate::transform::{Transform, TransformInPlace};
+    use geojson::{Feature, GeoJson, Geometry, Value};
+
+    fn assert_almost_eq(a: f64, b: f64) {
+        let f: f64 = a / b;
+        assert!(f < 1.00001);
+        assert!(f > 0.99999);
+    }
+
+    fn ft_to_m() -> Proj {
+        Proj::new_known_crs("EPSG:2230", "EPSG:26946", None).unwrap()
+    }
+
+    #[test]
+    fn test_transform_feature() {
+        let mut feature: Feature = r#"{
+            "type": "Feature",
+            "bbox": [4760096.421921, 3744293.729449, 4760197.421921, 3744394.729449],
+            "geometry": {
+                "type": "LineString",
+                "coordinates": [[4760096.421921, 3744293.729449], [4760197.421921, 3744394.729449]]
+            },
+            "properties": {"name": "Oceanside"},
+            "title": "foreign"
+        }"#
+        .parse()
+        .unwrap();
+        feature.transform_in_place(&ft_to_m()).unwrap();
+        match &feature.geometry.as_ref().unwrap().value {
+            Value::LineString(positions) => {
+                assert_almost_eq(positions[0][0], 1450880.29);
+                assert_almost_eq(positions[0][1], 1141263.01);
+            }
+            _ => panic!("expected a LineString"),
+        }
+        let bbox = feature.bbox.as_ref().unwrap();
+        assert_eq!(bbox.len(), 4);
+        assert_almost_eq(bbox[0], 1450880.29);
+        assert_almost_eq(bbox[3], 1141293.79);
+        assert_eq!(feature.property("name").unwrap(), "Oceanside");
+        assert_eq!(
+            feature.foreign_members.as_ref().unwrap()["title"],
+            "foreign"
+        );
+    }
+
+    #[test]
+    fn test_transform_3d_positions() {
+        let ecef = Proj::new_known_crs("EPSG:4979", "EPSG:4978", None).unwrap();
+        let geometry = Geometry {
+            bbox: Some(vec![0.0, 0.0, 0.0, 1.0, 1.0, 100.0]),
+            value: Value::MultiPoint(vec![vec![0.0, 0.0, 0.0], vec![1.0, 1.0, 100.0, 7.0]]),
+            foreign_members: None,
+        };
+        let result = geometry.transform(&ecef).unwrap();
+        match &result.value {
+            Value::MultiPoint(positions) => {
+                assert_almost_eq(positions[0][0], 6378137.0);
+                assert_eq!(positions[0][2], 0.0);
+                assert_almost_eq(positions[1][2], 110570.52);
+                // the measure is left unchanged
+                assert_eq!(positions[1][3], 7.0);
+            }
+            _ => panic!("expected a MultiPoint"),
+        }
+        assert_eq!(result.bbox.unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_transform_invalid_position() {
+        let mut geometry = Geometry::new(Value::Point(vec![1.0]));
+        assert!(geometry.transform_in_place(&ft_to_m()).is_err());
+        assert_eq!(geometry.value, Value::Point(vec![1.0]));
+    }
+
+    #[test]
+    fn test_reproject_features() {
+        let input = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "geometry": {"type": "Point", "coordinates": [4760096.421921, 3744293.729449]}, "properties": {"id": 1}},
+            {"type": "Feature", "geometry": null, "properties": {"id": 2}}
+        ]}"#;
+        let mut output = vec![];
+        let count = reproject_features(&ft_to_m(), input.as_bytes(), &mut output).unwrap();
+        assert_eq!(count, 2);
+        let output: GeoJson = String::from_utf8(output).unwrap().parse().unwrap();
+        match output {
+            GeoJson::FeatureCollection(collection) => {
+                assert_eq!(collection.features.len(), 2);
+                assert_eq!(collection.features[1].property("id").unwrap(), 2);
+            }
+            _ => panic!("expected a FeatureCollection"),
+        }
+    }
+
+    #[test]
+    fn test_reproject_geojson_lines() {
+        let input = concat!(
+            "\u{1e}{\"type\": \"Point\", \"coordinates\": [4760096.421921, 3744293.729449]}\n",
+            "\n",
+            "{\"type\": \"Feature\", \"geometry\": null, \"properties\": null}\n",
+        );
+        let mut output = vec![];
+        let count = reproject_geojson_lines(&ft_to_m(), input.as_bytes(), &mut output).unwrap();
+        assert_eq!(count, 2);
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let point: GeoJson = lines[0].parse().unwrap();
+        match point {
+            GeoJson::Geometry(Geometry {
+                value: Value::Point(position),
+                ..
+            }) => assert_almost_eq(position[0], 1450880.29),
+            _ => panic!("expected a Point"),
+        }
+        assert!(reproject_geojson_lines(&ft_to_m(), "{".as_bytes(), vec![]).is_err());
+    }
+}
@@ -0,0 +1,194 @@
+use crate::cache::TransformerCache;
+use crate::proj::ProjError;
+use crate::transform::TransformInPlace;
+use num_traits::Float;
+
+thread_local! {
+    /// The transformers used by `Georeferenced::to_crs` on this thread
+    static TRANSFORMERS: TransformerCache = TransformerCache::new(32);
+}
+
+/// A geometry tagged with the coordinate reference system its coordinates are in
+///
+/// The CRS can be anything accepted by [`Proj::new_known_crs`](struct.Proj.html#method.new_known_crs),
+/// e.g. `"EPSG:4326"`. As with `new_known_crs`, coordinates are in `Longitude, Latitude` /
+/// `Easting, Northing` order, and geographic coordinates are in degrees.
+///
+/// [`to_crs`](#method.to_crs) returns the geometry in another CRS, using a transformer which is
+/// created on first use and kept in a per-thread [`TransformerCache`](struct.TransformerCache.html)
+/// for subsequent conversions between the same CRSs. Use [`to_crs_with_cache`](#method.to_crs_with_cache)
+/// to share a cache between threads, or to control its size.
+///
+///```rust
+/// # use assert_approx_eq::assert_approx_eq;
+/// extern crate proj;
+/// use proj::Georeferenced;
+///
+/// extern crate geo_types;
+/// use geo_types::Point;
+///
+/// let ft = Georeferenced::new(Point::new(4760096.421921f64, 3744293.729449f64), "EPSG:2230");
+/// let m = ft.to_crs("EPSG:26946").unwrap();
+/// assert_eq!(m.crs(), "EPSG:26946");
+/// assert_approx_eq!(m.geometry().x(), 1450880.29, 1e-2);
+/// assert_approx_eq!(m.geometry().y(), 1141263.01, 1e-2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Georeferenced<G> {
+    geometry: G,
+    crs: String,
+}
+
+impl<G> Georeferenced<G> {
+    /// Tag a geometry with the CRS its coordinates are in
+    pub fn new(geometry: G, crs: &str) -> Self {
+        Georeferenced {
+            geometry,
+            crs: crs.to_string(),
+        }
+    }
+
+    /// The geometry
+    pub fn geometry(&self) -> &G {
+        &self.geometry
+    }
+
+    /// The geometry, for modification in its current CRS
+    pub fn geometry_mut(&mut self) -> &mut G {
+        &mut self.geometry
+    }
+
+    /// The CRS of the geometry
+    pub fn crs(&self) -> &str {
+        &self.crs
+    }
+
+    /// Discard the CRS, returning the geometry
+    pub fn into_geometry(self) -> G {
+        self.geometry
+    }
+
+    /// Split into the geometry and its CRS
+    pub fn into_parts(self) -> (G, String) {
+        (self.geometry, self.crs)
+    }
+
+    /// Return a copy of the geometry, transformed to the `target` CRS
+    ///
+    /// If `target` is identical to the current CRS, the geometry is copied unchanged.
+    pub fn to_crs<T>(&self, target: &str) -> Result<Georeferenced<G>, ProjError>
+    where
+        T: Float,
+        G: TransformInPlace<T> + Clone,
+    {
+        let mut result = self.clone();
+        result.to_crs_in_place(target)?;
+        Ok(result)
+    }
+
+    /// Return a copy of the geometry, transformed to the `target` CRS using a transformer from `cache`
+    pub fn to_crs_with_cache<T>(
+        &self,
+        target: &str,
+        cache: &TransformerCache,
+    ) -> Result<Georeferenced<G>, ProjError>
+    where
+        T: Float,
+        G: TransformInPlace<T> + Clone,
+    {
+        let mut result = self.clone();
+        result.to_crs_in_place_with_cache(target, cache)?;
+        Ok(result)
+    }
+
+    /// Transform the geometry to the `target` CRS in place
+    ///
+    /// If the transformation fails, the geometry and its CRS are left unchanged.
+    pub fn to_crs_in_place<T>(&mut self, target: &str) -> Result<(), ProjError>
+    where
+        T: Float,
+        G: TransformInPlace<T>,
+    {
+        TRANSFORMERS.with(|cache| self.to_crs_in_place_with_cache(target, cache))
+    }
+
+    /// Transform the geometry to the `target` CRS in place, using a transformer from `cache`
+    ///
+    /// If the transformation fails, the geometry and its CRS are left unchanged.
+    pub fn to_crs_in_place_with_cache<T>(
+        &mut self,
+        target: &str,
+        cache: &TransformerCache,
+    ) -> Result<(), ProjError>
+    where
+        T: Float,
+        G: TransformInPlace<T>,
+    {
+        if self.crs != target {
+            let proj = cache.get(&self.crs, target, None)?;
+            self.geometry.transform_in_place(&proj.lock().unwrap())?;
+            self.crs = target.to_string();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Georeferenced;
+    use crate::cache::TransformerCache;
+    use geo_types::{LineString, Point};
+
+    fn assert_almost_eq(a: f64, b: f64) {
+        let f: f64 = a / b;
+        assert!(f < 1.00001);
+        assert!(f > 0.99999);
+    }
+
+    #[test]
+    fn test_to_crs() {
+        let line = Georeferenced::new(
+            LineString::from(vec![
+                (4760096.421921, 3744293.729449),
+                (4760197.421921, 3744394.729449),
+            ]),
+            "EPSG:2230",
+        );
+        let result = line.to_crs("EPSG:26946").unwrap();
+        assert_eq!(result.crs(), "EPSG:26946");
+        assert_almost_eq(result.geometry().0[0].x, 1450880.29);
+        assert_almost_eq(result.geometry().0[1].y, 1141293.79);
+        // the original is untouched
+        assert_eq!(line.geometry().0[0].x, 4760096.421921);
+        // and converting back uses a new transformer
+        let back = result.to_crs("EPSG:2230").unwrap();
+        assert_almost_eq(back.geometry().0[0].x, 4760096.421921);
+    }
+
+    #[test]
+    fn test_to_crs_with_cache() {
+        let cache = TransformerCache::new(4);
+        let point = Georeferenced::new(Point::new(4760096.421921, 3744293.729449), "EPSG:2230");
+        for _ in 0..2 {
+            let result = point.to_crs_with_cache("EPSG:26946", &cache).unwrap();
+            assert_almost_eq(result.geometry().x(), 1450880.29);
+        }
+        let stats = cache.stats();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 1);
+    }
+
+    #[test]
+    fn test_to_same_crs() {
+        let point = Georeferenced::new(Point::new(1.0, 2.0), "EPSG:4326");
+        assert_eq!(point.to_crs("EPSG:4326").unwrap(), point);
+    }
+
+    #[test]
+    fn test_to_crs_in_place_invalid() {
+        let mut point = Georeferenced::new(Point::new(1.0, 2.0), "EPSG:4326");
+        assert!(point.to_crs_in_place("EPSG:nonsense").is_err());
+        assert_eq!(point.crs(), "EPSG:4326");
+        assert_eq!(point.into_geometry(), Point::new(1.0, 2.0));
+    }
+}
@@ -0,0 +1,313 @@
+use crate::proj::{_string, context_error, Area, ProjBuilder, ProjError};
+use libc::{c_char, c_double, c_int, c_void};
+use proj_sys::{
+    proj_coordoperation_get_grid_used, proj_coordoperation_get_grid_used_count, proj_create,
+    proj_create_operation_factory_context, proj_create_operations, proj_destroy,
+    proj_download_file, proj_is_download_needed, proj_list_destroy, proj_list_get,
+    proj_list_get_count, proj_operation_factory_context_destroy,
+    proj_operation_factory_context_set_area_of_interest,
+    proj_operation_factory_context_set_grid_availability_use,
+    proj_operation_factory_context_set_spatial_criterion, PJconsts, PJ_CONTEXT,
+    PJ_OPERATION_FACTORY_CONTEXT, PROJ_GRID_AVAILABILITY_USE_PROJ_GRID_AVAILABILITY_IGNORED,
+    PROJ_SPATIAL_CRITERION_PROJ_SPATIAL_CRITERION_PARTIAL_INTERSECTION,
+};
+use std::collections::BTreeMap;
+use std::ffi::CString;
+use std::ptr;
+
+/// A grid used by a coordinate operation
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Grid {
+    /// The name of the grid, e.g. `us_noaa_conus.tif`
+    pub short_name: String,
+    /// The full path of the grid, if it is available locally
+    pub full_name: Option<String>,
+    /// The name of the package containing the grid, if any
+    pub package_name: Option<String>,
+    /// The URL from which the grid (or its package) can be downloaded, if any
+    pub url: Option<String>,
+    /// Whether `url` points directly at the grid, rather than a package
+    pub direct_download: bool,
+    /// Whether the grid is released under an open license
+    pub open_license: bool,
+    /// Whether the grid is available, either locally or through the network
+    pub available: bool,
+}
+
+// Convert a possibly-null or empty string from PROJ
+fn optional_string(raw_ptr: *const c_char) -> Option<String> {
+    if raw_ptr.is_null() {
+        None
+    } else {
+        Some(_string(raw_ptr)).filter(|s| !s.is_empty())
+    }
+}
+
+/// Collect the grids used by a coordinate operation
+unsafe fn grids_used(
+    ctx: *mut PJ_CONTEXT,
+    op: *const PJconsts,
+    grids: &mut BTreeMap<String, Grid>,
+) {
+    for index in 0..proj_coordoperation_get_grid_used_count(ctx, op) {
+        let mut short_name = ptr::null();
+        let mut full_name = ptr::null();
+        let mut package_name = ptr::null();
+        let mut url = ptr::null();
+        let mut direct_download = 0;
+        let mut open_license = 0;
+        let mut available = 0;
+        let found = proj_coordoperation_get_grid_used(
+            ctx,
+            op,
+            index,
+            &mut short_name,
+            &mut full_name,
+            &mut package_name,
+            &mut url,
+            &mut direct_download,
+            &mut open_license,
+            &mut available,
+        );
+        if found == 0 || short_name.is_null() {
+            continue;
+        }
+        let grid = Grid {
+            short_name: _string(short_name),
+            full_name: optional_string(full_name),
+            package_name: optional_string(package_name),
+            url: optional_string(url),
+            direct_download: direct_download != 0,
+            open_license: open_license != 0,
+            available: available != 0,
+        };
+        grids.entry(grid.short_name.clone()).or_insert(grid);
+    }
+}
+
+unsafe extern "C" fn progress_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    pct: c_double,
+    user_data: *mut c_void,
+) -> c_int {
+    let progress = &mut *(user_data as *mut &mut dyn FnMut(f64) -> bool);
+    progress(pct) as c_int
+}
+
+impl ProjBuilder {
+    /// Find the grids which may be used when transforming between each pair of CRS
+    /// within an area of interest
+    ///
+    /// All candidate operations whose area of use intersects `area` are considered, regardless
+    /// of whether their grids are currently available. `from` and `to` can be any CRS definition
+    /// accepted by [`Proj::new_known_crs()`](struct.Proj.html#method.new_known_crs).
+    /// The grids are returned in order of their `short_name`, without duplicates.
+    ///
+    ///```rust
+    /// extern crate proj;
+    /// use proj::{Area, ProjBuilder};
+    ///
+    /// let builder = ProjBuilder::new();
+    /// let kansas = Area::new(-102.05, 36.99, -94.58, 40.01);
+    /// let grids = builder
+    ///     .grids_for_area(kansas, &[("EPSG:4267", "EPSG:4269")])
+    ///     .unwrap();
+    /// assert!(grids.iter().any(|grid| grid.short_name.contains("conus")));
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn grids_for_area(
+        &self,
+        area: Area,
+        crs_pairs: &[(&str, &str)],
+    ) -> Result<Vec<Grid>, ProjError> {
+        let mut grids = BTreeMap::new();
+        unsafe {
+            let factory = proj_create_operation_factory_context(self.ctx, ptr::null());
+            if factory.is_null() {
+                return Err(ProjError::Operations(context_error(self.ctx)));
+            }
+            proj_operation_factory_context_set_area_of_interest(
+                self.ctx,
+                factory,
+                area.west(),
+                area.south(),
+                area.east(),
+                area.north(),
+            );
+            proj_operation_factory_context_set_spatial_criterion(
+                self.ctx,
+                factory,
+                PROJ_SPATIAL_CRITERION_PROJ_SPATIAL_CRITERION_PARTIAL_INTERSECTION,
+            );
+            proj_operation_factory_context_set_grid_availability_use(
+                self.ctx,
+                factory,
+                PROJ_GRID_AVAILABILITY_USE_PROJ_GRID_AVAILABILITY_IGNORED,
+            );
+            let result = crs_pairs
+                .iter()
+                .try_for_each(|(from, to)| self.pair_grids(factory, from, to, &mut grids));
+            proj_operation_factory_context_destroy(factory);
+            result?;
+        }
+        Ok(grids.into_values().collect())
+    }
+
+    /// Collect the grids used by the candidate operations between a single pair of CRS
+    unsafe fn pair_grids(
+        &self,
+        factory: *mut PJ_OPERATION_FACTORY_CONTEXT,
+        from: &str,
+        to: &str,
+        grids: &mut BTreeMap<String, Grid>,
+    ) -> Result<(), ProjError> {
+        let from_c = CString::new(from).unwrap();
+        let to_c = CString::new(to).unwrap();
+        let source = proj_create(self.ctx, from_c.as_ptr());
+        let target = proj_create(self.ctx, to_c.as_ptr());
+        if source.is_null() || target.is_null() {
+            let err = context_error(self.ctx);
+            proj_destroy(source);
+            proj_destroy(target);
+            return Err(ProjError::Operations(format!(
+                "{} to {}: {}",
+                from, to, err
+            )));
+        }
+        let ops = proj_create_operations(self.ctx, source, target, factory);
+        proj_destroy(source);
+        proj_destroy(target);
+        if ops.is_null() {
+            return Err(ProjError::Operations(format!(
+                "{} to {}: {}",
+                from,
+                to,
+                context_error(self.ctx)
+            )));
+        }
+        for index in 0..proj_list_get_count(ops) {
+            let op = proj_list_get(self.ctx, ops, index);
+            if !op.is_null() {
+                grids_used(self.ctx, op, grids);
+                proj_destroy(op);
+            }
+        }
+        proj_list_destroy(ops);
+        Ok(())
+    }
+
+    /// Download grids into the `PROJ` user writable directory, returning the number of grids downloaded
+    ///
+    /// Grids are fetched from the configured [URL endpoint](#method.set_url_endpoint), so a local
+    /// mirror may be used. Network access must be [enabled](#method.enable_network).
+    /// Grids which have already been downloaded, and are up to date, are skipped.
+    ///
+    /// `progress` is called with each grid, and the fraction (from 0 to 1) of it which has been downloaded.
+    /// Returning `false` cancels the prefetch.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn prefetch_grids<F>(&self, grids: &[Grid], mut progress: F) -> Result<usize, ProjError>
+    where
+        F: FnMut(&Grid, f64) -> bool,
+    {
+        let mut downloaded = 0;
+        for grid in grids {
+            let name = CString::new(grid.short_name.as_str()).unwrap();
+            if unsafe { proj_is_download_needed(self.ctx, name.as_ptr(), 0) } == 0 {
+                continue;
+            }
+            let mut grid_progress = |pct: f64| progress(grid, pct);
+            let mut grid_progress: &mut dyn FnMut(f64) -> bool = &mut grid_progress;
+            let user_data = &mut grid_progress as *mut &mut dyn FnMut(f64) -> bool as *mut c_void;
+            let result = unsafe {
+                proj_download_file(self.ctx, name.as_ptr(), 0, Some(progress_cbk), user_data)
+            };
+            if result == 0 {
+                return Err(ProjError::Download(format!(
+                    "{}: {}",
+                    grid.short_name,
+                    context_error(self.ctx)
+                )));
+            }
+            downloaded += 1;
+        }
+        Ok(downloaded)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Grid;
+    use crate::network::{NetworkApi, NetworkResponse};
+    use crate::proj::{Area, ProjBuilder};
+    use std::sync::{Arc, Mutex};
+
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl NetworkApi for Recorder {
+        fn read_range(
+            &self,
+            url: &str,
+            _offset: u64,
+            _size: usize,
+        ) -> Result<NetworkResponse, String> {
+            self.0.lock().unwrap().push(url.to_string());
+            Err("404 Not Found".to_string())
+        }
+    }
+
+    #[test]
+    fn test_grids_for_area() {
+        let builder = ProjBuilder::new();
+        let kansas = Area::new(-102.05, 36.99, -94.58, 40.01);
+        let grids = builder
+            .grids_for_area(
+                kansas,
+                &[("EPSG:4267", "EPSG:4269"), ("EPSG:4267", "EPSG:4269")],
+            )
+            .unwrap();
+        assert!(grids.iter().any(|grid| grid.short_name.contains("conus")));
+        // grids are sorted and unique
+        assert!(grids
+            .windows(2)
+            .all(|pair| pair[0].short_name < pair[1].short_name));
+    }
+
+    #[test]
+    fn test_grids_for_area_invalid_crs() {
+        let builder = ProjBuilder::new();
+        let area = Area::new(-1.0, -1.0, 1.0, 1.0);
+        assert!(builder
+            .grids_for_area(area, &[("EPSG:4326", "NOT:A_CRS")])
+            .is_err());
+    }
+
+    #[test]
+    fn test_prefetch_from_mirror() {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let mut builder = ProjBuilder::new();
+        builder
+            .set_network_api(Recorder(Arc::clone(&requests)))
+            .unwrap();
+        builder.enable_network(true).unwrap();
+        builder.set_url_endpoint("http://mirror.invalid/proj");
+        let missing = Grid {
+            short_name: "rust_proj_missing_grid.tif".to_string(),
+            full_name: None,
+            package_name: None,
+            url: None,
+            direct_download: true,
+            open_license: true,
+            available: false,
+        };
+        assert!(builder.prefetch_grids(&[missing], |_, _| true).is_err());
+        assert!(requests
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|url| url == "http://mirror.invalid/proj/rust_proj_missing_grid.tif"));
+    }
+}
@@ -0,0 +1,221 @@
+use crate::proj::{_string, ProjError};
+use libc::c_char;
+use proj_sys::{
+    proj_context_create, proj_context_destroy, proj_context_set_enable_network, proj_grid_info,
+    proj_info, proj_init_info,
+};
+use std::ffi::CString;
+use std::slice;
+
+/// Information about the `PROJ` library linked at runtime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Info {
+    /// The major version number
+    pub major: u32,
+    /// The minor version number
+    pub minor: u32,
+    /// The patch version number
+    pub patch: u32,
+    /// The release description, e.g. `Rel. 7.0.0, March 1st, 2020`
+    pub release: String,
+    /// The version string, e.g. `7.0.0`
+    pub version: String,
+    /// The directories searched for resource files, in order
+    pub search_paths: Vec<String>,
+    /// Whether `PROJ` was built with support for fetching remote grids
+    pub network: bool,
+}
+
+impl Info {
+    /// Whether the `PROJ` version is at least `major.minor.patch`
+    pub fn version_at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+        (self.major, self.minor, self.patch) >= (major, minor, patch)
+    }
+}
+
+/// Information about a grid file
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridInfo {
+    /// The name of the grid
+    pub name: String,
+    /// The full path of the grid file
+    pub filename: String,
+    /// The format of the grid, e.g. `gtiff` or `ntv2`
+    pub format: String,
+    /// Longitude and latitude of the lower-left corner of the grid, in radians
+    pub lower_left: (f64, f64),
+    /// Longitude and latitude of the upper-right corner of the grid, in radians
+    pub upper_right: (f64, f64),
+    /// The number of grid cells in the longitudinal direction
+    pub n_lon: u32,
+    /// The number of grid cells in the latitudinal direction
+    pub n_lat: u32,
+    /// The longitudinal cell size, in radians
+    pub cell_size_lon: f64,
+    /// The latitudinal cell size, in radians
+    pub cell_size_lat: f64,
+}
+
+/// Information about an init file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitInfo {
+    /// The name of the init file
+    pub name: String,
+    /// The full path of the init file, which may be empty if it is backed by the `PROJ` database
+    pub filename: String,
+    /// The version of the init file
+    pub version: String,
+    /// The originating entity of the init file, e.g. `EPSG`
+    pub origin: String,
+    /// The date of the last update of the init file
+    pub last_update: String,
+}
+
+/// Get a String from a fixed-size, NUL-terminated buffer
+fn array_string(buf: &[c_char]) -> String {
+    let bytes = unsafe { slice::from_raw_parts(buf.as_ptr() as *const u8, buf.len()) };
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+/// Get information about the `PROJ` library linked at runtime
+///
+/// This may differ from the version the crate was built against.
+///
+///```rust
+/// extern crate proj;
+///
+/// let info = proj::info();
+/// assert!(info.version_at_least(7, 0, 0));
+/// ```
+///
+/// # Safety
+/// This method contains unsafe code.
+pub fn info() -> Info {
+    let raw = unsafe { proj_info() };
+    let search_paths = if raw.paths.is_null() {
+        vec![]
+    } else {
+        unsafe { slice::from_raw_parts(raw.paths, raw.path_count) }
+            .iter()
+            .map(|&path| _string(path))
+            .collect()
+    };
+    // enabling network access fails if PROJ was built without it
+    let network = unsafe {
+        let ctx = proj_context_create();
+        let enabled = proj_context_set_enable_network(ctx, 1) != 0;
+        proj_context_destroy(ctx);
+        enabled
+    };
+    Info {
+        major: raw.major as u32,
+        minor: raw.minor as u32,
+        patch: raw.patch as u32,
+        release: _string(raw.release),
+        version: _string(raw.version),
+        search_paths,
+        network,
+    }
+}
+
+/// Check that the `PROJ` library linked at runtime is at least version `major.minor.patch`
+///
+/// This allows applications depending on behaviour introduced in a particular `PROJ`
+/// release to fail fast on older installations.
+///
+/// # Safety
+/// This method contains unsafe code.
+pub fn require_version(major: u32, minor: u32, patch: u32) -> Result<Info, ProjError> {
+    let info = info();
+    if info.version_at_least(major, minor, patch) {
+        Ok(info)
+    } else {
+        Err(ProjError::Version(format!(
+            "PROJ {}.{}.{} is required, but {} is installed",
+            major, minor, patch, info.version
+        )))
+    }
+}
+
+/// Get information about a grid file, if it can be found
+///
+/// # Safety
+/// This method contains unsafe code.
+pub fn grid_info(name: &str) -> Option<GridInfo> {
+    let c_name = CString::new(name).ok()?;
+    let raw = unsafe { proj_grid_info(c_name.as_ptr()) };
+    let format = array_string(&raw.format);
+    if format == "missing" || array_string(&raw.filename).is_empty() {
+        return None;
+    }
+    Some(GridInfo {
+        name: array_string(&raw.gridname),
+        filename: array_string(&raw.filename),
+        format,
+        lower_left: (raw.lowerleft.lam, raw.lowerleft.phi),
+        upper_right: (raw.upperright.lam, raw.upperright.phi),
+        n_lon: raw.n_lon as u32,
+        n_lat: raw.n_lat as u32,
+        cell_size_lon: raw.cs_lon,
+        cell_size_lat: raw.cs_lat,
+    })
+}
+
+/// Get information about an init file (e.g. `epsg`), if it can be found
+///
+/// # Safety
+/// This method contains unsafe code.
+pub fn init_info(name: &str) -> Option<InitInfo> {
+    let c_name = CString::new(name).ok()?;
+    let raw = unsafe { proj_init_info(c_name.as_ptr()) };
+    let version = array_string(&raw.version);
+    let origin = array_string(&raw.origin);
+    // init files answered from the database, such as epsg, may have no filename
+    if version.is_empty() && origin.is_empty() {
+        return None;
+    }
+    Some(InitInfo {
+        name: array_string(&raw.name),
+        filename: array_string(&raw.filename),
+        version,
+        origin,
+        last_update: array_string(&raw.lastupdate),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{grid_info, info, init_info, require_version};
+
+    #[test]
+    fn test_info() {
+        let info = info();
+        assert!(info.major >= 7);
+        assert!(info
+            .version
+            .starts_with(&format!("{}.{}.", info.major, info.minor)));
+        assert!(info.release.contains(&info.version));
+        assert!(info.version_at_least(info.major, info.minor, info.patch));
+        assert!(!info.version_at_least(info.major + 1, 0, 0));
+    }
+
+    #[test]
+    fn test_require_version() {
+        assert!(require_version(7, 0, 0).is_ok());
+        assert!(require_version(99, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_grid_info_missing() {
+        assert!(grid_info("rust_proj_missing_grid.tif").is_none());
+    }
+
+    #[test]
+    fn test_init_info() {
+        let epsg = init_info("epsg").unwrap();
+        assert_eq!(epsg.origin, "EPSG");
+        assert!(!epsg.version.is_empty());
+        assert!(init_info("rust_proj_missing_init").is_none());
+    }
+}
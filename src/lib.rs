@@ -34,7 +34,54 @@
 //! assert_approx_eq!(result.y(), 1141263.01f64, 1.0e-2);
 //! ```
 
+mod cache;
+mod catalogue;
+mod coord;
+mod crs;
+mod dms;
+mod fileapi;
+mod geocentric;
+mod geodesic;
+#[cfg(feature = "with-geojson")]
+mod geojson_reproject;
+mod georeferenced;
+mod grids;
+mod info;
+mod network;
+mod operations;
+mod pipeline;
 mod proj;
+mod transform;
+mod wkb;
+mod wkt;
 
+pub use crate::cache::{CacheStats, TransformerCache, TransformerOptions};
+pub use crate::catalogue::{
+    list_crs, list_ellipsoids, list_operations, list_prime_meridians, list_units, CrsInfo,
+    EllipsoidInfo, OperationInfo, PrimeMeridianInfo, UnitCategory, UnitInfo,
+};
+pub use crate::coord::Coord;
+pub use crate::crs::{
+    AreaOfUse, Axis, CompoundCrs, Conversion, CrsType, Datum, DerivedGeographicCrs, Ellipsoid,
+    GeographicCrs, PrimeMeridian, ProjectedCrs, Unit,
+};
+pub use crate::dms::{dms_point, dms_to_degrees, dms_to_radians, radians_to_dms, DmsFormat};
+pub use crate::fileapi::{FileApi, FileHandle, MemoryFileApi, OpenAccess};
+pub use crate::geocentric::TopocentricOrigin;
+pub use crate::geodesic::{Geod, GeodesicInverse};
+#[cfg(feature = "with-geojson")]
+pub use crate::geojson_reproject::{reproject_features, reproject_geojson_lines};
+pub use crate::georeferenced::Georeferenced;
+pub use crate::grids::Grid;
+pub use crate::info::{grid_info, info, init_info, require_version, GridInfo, Info, InitInfo};
+pub use crate::network::{NetworkApi, NetworkResponse};
+pub use crate::operations::CoordinateOperation;
+pub use crate::pipeline::{Helmert, Param, Pipeline, RotationConvention, Step};
 pub use crate::proj::Area;
+pub use crate::proj::Factors;
 pub use crate::proj::Proj;
+pub use crate::proj::ProjBuilder;
+pub use crate::proj::ProjError;
+pub use crate::transform::{Transform, TransformInPlace};
+pub use crate::wkb::{reproject_wkb, reproject_wkb_with_srid};
+pub use crate::wkt::{reproject_wkt, reproject_wkt_with_srid};
@@ -0,0 +1,370 @@
+use crate::proj::{_string, context_error, ProjBuilder, ProjError};
+use libc::{c_char, c_int, c_ulonglong, c_void, size_t};
+use proj_sys::{
+    proj_context_is_network_enabled, proj_context_set_enable_network,
+    proj_context_set_network_callbacks, proj_context_set_url_endpoint, proj_grid_cache_clear,
+    proj_grid_cache_set_enable, proj_grid_cache_set_filename, proj_grid_cache_set_max_size,
+    proj_grid_cache_set_ttl, PJ_CONTEXT, PROJ_NETWORK_HANDLE,
+};
+use std::ffi::CString;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+use std::time::Duration;
+
+/// The response to a ranged HTTP GET request
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkResponse {
+    /// The bytes returned by the request
+    pub data: Vec<u8>,
+    /// The response headers, as `(name, value)` pairs
+    ///
+    /// `PROJ` uses `Content-Range`, `Last-Modified` and `ETag` to manage its grid cache.
+    pub headers: Vec<(String, String)>,
+}
+
+/// An HTTP client used by `PROJ` to fetch remote grids
+///
+/// Once installed using
+/// [`ProjBuilder::set_network_api()`](struct.ProjBuilder.html#method.set_network_api),
+/// this replaces the `libcurl`-based client built into `PROJ`. Network access must also be enabled
+/// using [`ProjBuilder::enable_network()`](struct.ProjBuilder.html#method.enable_network).
+/// A panic in `read_range` is caught, and treated as a failed request.
+pub trait NetworkApi: Send {
+    /// Read `size` bytes of the resource at `url`, starting at `offset`
+    ///
+    /// This corresponds to a GET request with a `Range: bytes=offset-(offset + size - 1)` header.
+    /// On failure, a description of the error should be returned.
+    fn read_range(&self, url: &str, offset: u64, size: usize) -> Result<NetworkResponse, String>;
+}
+
+/// The user data passed to the network callbacks
+pub(crate) struct NetworkHandler {
+    api: Box<dyn NetworkApi>,
+}
+
+// The state of a remote resource opened by PROJ
+struct NetworkHandle {
+    url: String,
+    headers: Vec<(String, CString)>,
+}
+
+impl ProjBuilder {
+    /// Enable or disable network access for fetching remote grids
+    ///
+    /// Enabling network access fails if `PROJ` was built without network support,
+    /// and no [`NetworkApi`](trait.NetworkApi.html) has been set.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn enable_network(&mut self, enable: bool) -> Result<(), ProjError> {
+        let enabled = unsafe { proj_context_set_enable_network(self.ctx, enable as c_int) };
+        if enable && enabled == 0 {
+            Err(ProjError::Context(
+                "network access is not available".to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Whether network access is enabled
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn network_enabled(&self) -> bool {
+        unsafe { proj_context_is_network_enabled(self.ctx) != 0 }
+    }
+
+    /// Set the URL from which remote grids are fetched
+    ///
+    /// The default is [https://cdn.proj.org](https://cdn.proj.org), unless overridden by
+    /// the `PROJ_NETWORK_ENDPOINT` environment variable or `proj.ini`.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn set_url_endpoint(&mut self, url: &str) {
+        let c_url = CString::new(url).unwrap();
+        unsafe { proj_context_set_url_endpoint(self.ctx, c_url.as_ptr()) }
+    }
+
+    /// Route all network requests made by `PROJ` through `api`
+    ///
+    /// See [`NetworkApi`](trait.NetworkApi.html) for details.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn set_network_api<N: NetworkApi + 'static>(&mut self, api: N) -> Result<(), ProjError> {
+        let handler = Box::new(NetworkHandler { api: Box::new(api) });
+        let user_data = &*handler as *const NetworkHandler as *mut c_void;
+        let result = unsafe {
+            proj_context_set_network_callbacks(
+                self.ctx,
+                Some(open_cbk),
+                Some(close_cbk),
+                Some(get_header_value_cbk),
+                Some(read_range_cbk),
+                user_data,
+            )
+        };
+        if result == 0 {
+            return Err(ProjError::Context(context_error(self.ctx)));
+        }
+        self.callbacks.network = Some(handler);
+        Ok(())
+    }
+
+    /// Enable or disable the local cache of grid chunks fetched from the network
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn grid_cache_enable(&mut self, enable: bool) {
+        unsafe { proj_grid_cache_set_enable(self.ctx, enable as c_int) }
+    }
+
+    /// Set the path of the grid cache database
+    ///
+    /// The default is `cache.db` in the `PROJ` user writable directory.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn set_grid_cache_filename(&mut self, path: &str) {
+        let c_path = CString::new(path).unwrap();
+        unsafe { proj_grid_cache_set_filename(self.ctx, c_path.as_ptr()) }
+    }
+
+    /// Set the maximum size of the grid cache in megabytes, or `None` for an unlimited size
+    ///
+    /// The default is 300 MB.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn set_grid_cache_max_size(&mut self, megabytes: Option<u32>) {
+        let max_size = megabytes.map_or(-1, |mb| mb.min(c_int::MAX as u32) as c_int);
+        unsafe { proj_grid_cache_set_max_size(self.ctx, max_size) }
+    }
+
+    /// Set how long cached grid properties are trusted before being checked against the server
+    ///
+    /// The default is one day.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn set_grid_cache_ttl(&mut self, ttl: Duration) {
+        let seconds = ttl.as_secs().min(c_int::MAX as u64) as c_int;
+        unsafe { proj_grid_cache_set_ttl(self.ctx, seconds) }
+    }
+
+    /// Remove all entries from the grid cache
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn clear_grid_cache(&mut self) {
+        unsafe { proj_grid_cache_clear(self.ctx) }
+    }
+}
+
+unsafe fn handler<'a>(user_data: *mut c_void) -> &'a NetworkHandler {
+    &*(user_data as *const NetworkHandler)
+}
+
+/// Copy a response into the buffer supplied by PROJ, returning the number of bytes copied
+unsafe fn copy_data(data: &[u8], size: size_t, buffer: *mut c_void) -> size_t {
+    let len = data.len().min(size);
+    // a slice can't be built from a null pointer, even an empty one
+    if buffer.is_null() || len == 0 {
+        return 0;
+    }
+    let buf = slice::from_raw_parts_mut(buffer as *mut u8, len);
+    buf.copy_from_slice(&data[..len]);
+    len
+}
+
+/// Request a range of `url` from the user's `NetworkApi`
+///
+/// A panic can't unwind into `PROJ`, so it is caught and reported as an error.
+unsafe fn read_range(
+    user_data: *mut c_void,
+    url: &str,
+    offset: c_ulonglong,
+    size: size_t,
+) -> Result<NetworkResponse, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        handler(user_data).api.read_range(url, offset, size)
+    }))
+    .unwrap_or_else(|_| Err(format!("the network client panicked while reading {}", url)))
+}
+
+/// Write a (possibly truncated) NUL-terminated error message into the buffer supplied by PROJ
+unsafe fn copy_error(error: &str, max_size: size_t, out: *mut c_char) {
+    if max_size == 0 || out.is_null() {
+        return;
+    }
+    let bytes = error.as_bytes();
+    let len = bytes.len().min(max_size - 1);
+    let buf = slice::from_raw_parts_mut(out as *mut u8, len + 1);
+    buf[..len].copy_from_slice(&bytes[..len]);
+    buf[len] = 0;
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "C" fn open_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    url: *const c_char,
+    offset: c_ulonglong,
+    size_to_read: size_t,
+    buffer: *mut c_void,
+    out_size_read: *mut size_t,
+    error_string_max_size: size_t,
+    out_error_string: *mut c_char,
+    user_data: *mut c_void,
+) -> *mut PROJ_NETWORK_HANDLE {
+    let url = _string(url);
+    match read_range(user_data, &url, offset, size_to_read) {
+        Ok(response) => {
+            *out_size_read = copy_data(&response.data, size_to_read, buffer);
+            let headers = response
+                .headers
+                .into_iter()
+                .filter_map(|(name, value)| CString::new(value).ok().map(|value| (name, value)))
+                .collect();
+            Box::into_raw(Box::new(NetworkHandle { url, headers })) as *mut PROJ_NETWORK_HANDLE
+        }
+        Err(error) => {
+            *out_size_read = 0;
+            copy_error(&error, error_string_max_size, out_error_string);
+            ptr::null_mut()
+        }
+    }
+}
+
+unsafe extern "C" fn close_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    handle: *mut PROJ_NETWORK_HANDLE,
+    _user_data: *mut c_void,
+) {
+    drop(Box::from_raw(handle as *mut NetworkHandle));
+}
+
+unsafe extern "C" fn get_header_value_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    handle: *mut PROJ_NETWORK_HANDLE,
+    header_name: *const c_char,
+    _user_data: *mut c_void,
+) -> *const c_char {
+    let handle = &*(handle as *const NetworkHandle);
+    let header_name = _string(header_name);
+    // header names are case-insensitive
+    handle
+        .headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(&header_name))
+        .map_or(ptr::null(), |(_, value)| value.as_ptr())
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "C" fn read_range_cbk(
+    _ctx: *mut PJ_CONTEXT,
+    handle: *mut PROJ_NETWORK_HANDLE,
+    offset: c_ulonglong,
+    size_to_read: size_t,
+    buffer: *mut c_void,
+    error_string_max_size: size_t,
+    out_error_string: *mut c_char,
+    user_data: *mut c_void,
+) -> size_t {
+    let handle = &*(handle as *const NetworkHandle);
+    match read_range(user_data, &handle.url, offset, size_to_read) {
+        Ok(response) => copy_data(&response.data, size_to_read, buffer),
+        Err(error) => {
+            copy_error(&error, error_string_max_size, out_error_string);
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{NetworkApi, NetworkResponse};
+    use crate::proj::ProjBuilder;
+    use std::sync::{Arc, Mutex};
+
+    // Records requested URLs, and serves nothing
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl NetworkApi for Recorder {
+        fn read_range(
+            &self,
+            url: &str,
+            _offset: u64,
+            _size: usize,
+        ) -> Result<NetworkResponse, String> {
+            self.0.lock().unwrap().push(url.to_string());
+            Err("404 Not Found".to_string())
+        }
+    }
+
+    #[test]
+    fn test_network_api() {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let mut builder = ProjBuilder::new();
+        builder
+            .set_network_api(Recorder(Arc::clone(&requests)))
+            .unwrap();
+        builder.enable_network(true).unwrap();
+        assert!(builder.network_enabled());
+        builder.set_url_endpoint("http://grids.invalid");
+        builder.grid_cache_enable(false);
+        // the grid only exists remotely, and the server can't supply it
+        assert!(builder
+            .proj("+proj=hgridshift +grids=rust_proj_missing_grid.tif")
+            .is_none());
+        let requests = requests.lock().unwrap();
+        assert!(!requests.is_empty());
+        assert!(requests
+            .iter()
+            .all(|url| url == "http://grids.invalid/rust_proj_missing_grid.tif"));
+    }
+
+    // A client with a bug
+    struct Panicker;
+
+    impl NetworkApi for Panicker {
+        fn read_range(
+            &self,
+            _url: &str,
+            _offset: u64,
+            _size: usize,
+        ) -> Result<NetworkResponse, String> {
+            panic!("the client failed")
+        }
+    }
+
+    #[test]
+    fn test_network_api_panic() {
+        let mut builder = ProjBuilder::new();
+        builder.set_network_api(Panicker).unwrap();
+        builder.enable_network(true).unwrap();
+        builder.set_url_endpoint("http://grids.invalid");
+        builder.grid_cache_enable(false);
+        // the panic is reported to PROJ as a failed request, rather than unwinding through it
+        assert!(builder
+            .proj("+proj=hgridshift +grids=rust_proj_missing_grid.tif")
+            .is_none());
+    }
+
+    #[test]
+    fn test_network_disabled() {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let mut builder = ProjBuilder::new();
+        builder
+            .set_network_api(Recorder(Arc::clone(&requests)))
+            .unwrap();
+        builder.enable_network(false).unwrap();
+        assert!(!builder.network_enabled());
+        assert!(builder
+            .proj("+proj=hgridshift +grids=rust_proj_missing_grid.tif")
+            .is_none());
+        assert!(requests.lock().unwrap().is_empty());
+    }
+}
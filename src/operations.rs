@@ -0,0 +1,189 @@
+use crate::crs::{area_of_use_of, AreaOfUse};
+use crate::proj::{_string, context_error, Area, ProjBuilder, ProjError};
+use proj_sys::{
+    proj_as_proj_string, proj_coordoperation_get_accuracy, proj_coordoperation_is_instantiable,
+    proj_create, proj_create_operation_factory_context, proj_create_operations, proj_destroy,
+    proj_get_name, proj_list_destroy, proj_list_get, proj_list_get_count,
+    proj_operation_factory_context_destroy, proj_operation_factory_context_set_area_of_interest,
+    proj_operation_factory_context_set_grid_availability_use,
+    proj_operation_factory_context_set_spatial_criterion, PJconsts, PJ_CONTEXT,
+    PJ_PROJ_STRING_TYPE_PJ_PROJ_5, PROJ_GRID_AVAILABILITY_USE_PROJ_GRID_AVAILABILITY_IGNORED,
+    PROJ_SPATIAL_CRITERION_PROJ_SPATIAL_CRITERION_PARTIAL_INTERSECTION,
+};
+use std::ffi::CString;
+use std::ptr;
+
+/// A candidate coordinate operation between two CRS
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoordinateOperation {
+    /// The name of the operation, e.g. `NAD27 to NAD83 (1)`
+    pub name: String,
+    /// The accuracy of the operation in metres, if it is known
+    pub accuracy: Option<f64>,
+    /// The operation as a `PROJ` pipeline, if it can be represented as one
+    pub proj_string: Option<String>,
+    /// Whether the operation can be used, i.e. whether the grids it needs are available
+    pub instantiable: bool,
+    /// The area in which the operation is valid, if it is known
+    pub area_of_use: Option<AreaOfUse>,
+}
+
+/// Describe a coordinate operation
+unsafe fn operation_of(ctx: *mut PJ_CONTEXT, op: *const PJconsts) -> CoordinateOperation {
+    let name = proj_get_name(op);
+    let proj_string = proj_as_proj_string(ctx, op, PJ_PROJ_STRING_TYPE_PJ_PROJ_5, ptr::null());
+    // an unknown accuracy is reported as -1
+    let accuracy = proj_coordoperation_get_accuracy(ctx, op);
+    CoordinateOperation {
+        name: if name.is_null() {
+            String::new()
+        } else {
+            _string(name)
+        },
+        accuracy: Some(accuracy).filter(|&accuracy| accuracy >= 0.0),
+        proj_string: if proj_string.is_null() {
+            None
+        } else {
+            Some(_string(proj_string))
+        },
+        instantiable: proj_coordoperation_is_instantiable(ctx, op) == 1,
+        area_of_use: area_of_use_of(ctx, op),
+    }
+}
+
+impl ProjBuilder {
+    /// Find the candidate coordinate operations between two CRS
+    ///
+    /// The operations are returned in the order of preference used by
+    /// [`proj_known_crs()`](#method.proj_known_crs), and include operations whose grids are
+    /// unavailable, which are not [`instantiable`](struct.CoordinateOperation.html#structfield.instantiable).
+    /// If `area` is given, only operations whose area of use intersects it are returned.
+    /// `from` and `to` can be any CRS definition accepted by
+    /// [`Proj::new_known_crs()`](struct.Proj.html#method.new_known_crs).
+    ///
+    ///```rust
+    /// extern crate proj;
+    /// use proj::ProjBuilder;
+    ///
+    /// let operations = ProjBuilder::new()
+    ///     .candidate_operations("EPSG:4267", "EPSG:4269", None)
+    ///     .unwrap();
+    /// assert!(operations.len() > 1);
+    /// assert!(operations.iter().any(|op| op.name.starts_with("NAD27 to NAD83")));
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn candidate_operations(
+        &self,
+        from: &str,
+        to: &str,
+        area: Option<Area>,
+    ) -> Result<Vec<CoordinateOperation>, ProjError> {
+        let from_c = CString::new(from).unwrap();
+        let to_c = CString::new(to).unwrap();
+        let error =
+            |ctx| ProjError::Operations(format!("{} to {}: {}", from, to, context_error(ctx)));
+        unsafe {
+            let source = proj_create(self.ctx, from_c.as_ptr());
+            let target = proj_create(self.ctx, to_c.as_ptr());
+            if source.is_null() || target.is_null() {
+                let err = error(self.ctx);
+                proj_destroy(source);
+                proj_destroy(target);
+                return Err(err);
+            }
+            let factory = proj_create_operation_factory_context(self.ctx, ptr::null());
+            if factory.is_null() {
+                proj_destroy(source);
+                proj_destroy(target);
+                return Err(error(self.ctx));
+            }
+            if let Some(area) = area {
+                proj_operation_factory_context_set_area_of_interest(
+                    self.ctx,
+                    factory,
+                    area.west(),
+                    area.south(),
+                    area.east(),
+                    area.north(),
+                );
+                proj_operation_factory_context_set_spatial_criterion(
+                    self.ctx,
+                    factory,
+                    PROJ_SPATIAL_CRITERION_PROJ_SPATIAL_CRITERION_PARTIAL_INTERSECTION,
+                );
+            }
+            proj_operation_factory_context_set_grid_availability_use(
+                self.ctx,
+                factory,
+                PROJ_GRID_AVAILABILITY_USE_PROJ_GRID_AVAILABILITY_IGNORED,
+            );
+            let ops = proj_create_operations(self.ctx, source, target, factory);
+            proj_operation_factory_context_destroy(factory);
+            proj_destroy(source);
+            proj_destroy(target);
+            if ops.is_null() {
+                return Err(error(self.ctx));
+            }
+            let operations = (0..proj_list_get_count(ops))
+                .filter_map(|index| {
+                    let op = proj_list_get(self.ctx, ops, index);
+                    if op.is_null() {
+                        return None;
+                    }
+                    let operation = operation_of(self.ctx, op);
+                    proj_destroy(op);
+                    Some(operation)
+                })
+                .collect();
+            proj_list_destroy(ops);
+            Ok(operations)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::proj::{Area, ProjBuilder, ProjError};
+
+    #[test]
+    fn test_candidate_operations() {
+        let operations = ProjBuilder::new()
+            .candidate_operations("EPSG:4267", "EPSG:4269", None)
+            .unwrap();
+        assert!(operations.len() > 1);
+        assert!(operations.iter().all(|op| !op.name.is_empty()));
+        assert!(operations.iter().any(|op| op.accuracy.is_some()));
+    }
+
+    #[test]
+    fn test_candidate_operations_in_area() {
+        let builder = ProjBuilder::new();
+        let all = builder
+            .candidate_operations("EPSG:4267", "EPSG:4269", None)
+            .unwrap();
+        let kansas = Area::new(-102.05, 36.99, -94.58, 40.01);
+        let in_kansas = builder
+            .candidate_operations("EPSG:4267", "EPSG:4269", Some(kansas))
+            .unwrap();
+        assert!(!in_kansas.is_empty());
+        assert!(in_kansas.len() < all.len());
+        // a conversion needs no grids
+        let utm = builder
+            .candidate_operations("EPSG:4326", "EPSG:32633", None)
+            .unwrap();
+        assert_eq!(utm[0].name, "UTM zone 33N");
+        assert!(utm[0].instantiable);
+        assert!(utm[0].proj_string.as_ref().unwrap().contains("+zone=33"));
+    }
+
+    #[test]
+    fn test_candidate_operations_unknown_crs() {
+        let result = ProjBuilder::new().candidate_operations("EPSG:4326", "EPSG:nonsense", None);
+        match result {
+            Err(ProjError::Operations(message)) => assert!(message.contains("EPSG:nonsense")),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,445 @@
+use crate::proj::{Proj, ProjError};
+use std::fmt;
+use std::str::FromStr;
+
+/// A parameter of a `PROJ` operation: either a flag such as `+no_defs`, or a `key=value` pair such as `+ellps=GRS80`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    /// The name of the parameter, without the leading `+`
+    pub key: String,
+    /// The value of the parameter, or `None` for a flag
+    pub value: Option<String>,
+}
+
+impl Param {
+    /// Create a `key=value` parameter
+    pub fn new<V: ToString>(key: &str, value: V) -> Self {
+        Param {
+            key: key.to_string(),
+            value: Some(value.to_string()),
+        }
+    }
+
+    /// Create a flag parameter
+    pub fn flag(key: &str) -> Self {
+        Param {
+            key: key.to_string(),
+            value: None,
+        }
+    }
+}
+
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value {
+            Some(ref value) => write!(f, "+{}={}", self.key, value),
+            None => write!(f, "+{}", self.key),
+        }
+    }
+}
+
+impl FromStr for Param {
+    type Err = ProjError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let token = s.trim_start_matches('+');
+        if token.is_empty() {
+            return Err(ProjError::Pipeline(format!("invalid parameter: {}", s)));
+        }
+        let mut parts = token.splitn(2, '=');
+        let key = parts.next().unwrap();
+        Ok(match parts.next() {
+            Some(value) => Param::new(key, value),
+            None => Param::flag(key),
+        })
+    }
+}
+
+/// The rotation convention of a Helmert transformation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RotationConvention {
+    /// Rotations are positive anticlockwise when viewed from the origin (`position_vector`)
+    PositionVector,
+    /// Rotations are positive clockwise when viewed from the origin (`coordinate_frame`)
+    CoordinateFrame,
+}
+
+/// A Helmert transformation, which can be used as a pipeline [`Step`](struct.Step.html)
+///
+/// Translations are in metres, rotations in arc seconds, and the scale in parts per million.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Helmert {
+    /// The x, y and z translations, in metres
+    pub translation: [f64; 3],
+    /// The rotations about the x, y and z axes, in arc seconds
+    pub rotation: [f64; 3],
+    /// The scale difference, in parts per million
+    pub scale: f64,
+    /// How the signs of the rotations are interpreted
+    pub convention: RotationConvention,
+}
+
+impl From<Helmert> for Step {
+    fn from(helmert: Helmert) -> Self {
+        let [x, y, z] = helmert.translation;
+        let [rx, ry, rz] = helmert.rotation;
+        let convention = match helmert.convention {
+            RotationConvention::PositionVector => "position_vector",
+            RotationConvention::CoordinateFrame => "coordinate_frame",
+        };
+        Step::new("helmert")
+            .param("x", x)
+            .param("y", y)
+            .param("z", z)
+            .param("rx", rx)
+            .param("ry", ry)
+            .param("rz", rz)
+            .param("s", helmert.scale)
+            .param("convention", convention)
+    }
+}
+
+/// A single step of a `PROJ` pipeline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    /// The name of the operation, e.g. `"tmerc"`
+    pub operation: String,
+    /// Whether the operation is run in reverse (`+inv`)
+    pub inverse: bool,
+    /// Whether the step is skipped when the pipeline is run forwards (`+omit_fwd`)
+    pub omit_fwd: bool,
+    /// Whether the step is skipped when the pipeline is run in reverse (`+omit_inv`)
+    pub omit_inv: bool,
+    /// The parameters of the operation, in order
+    pub params: Vec<Param>,
+}
+
+impl Step {
+    /// Create a step using the given operation, e.g. `"tmerc"`
+    pub fn new(operation: &str) -> Self {
+        Step {
+            operation: operation.to_string(),
+            inverse: false,
+            omit_fwd: false,
+            omit_inv: false,
+            params: vec![],
+        }
+    }
+
+    /// Add a `key=value` parameter
+    pub fn param<V: ToString>(mut self, key: &str, value: V) -> Self {
+        self.params.push(Param::new(key, value));
+        self
+    }
+
+    /// Add a flag parameter
+    pub fn flag(mut self, key: &str) -> Self {
+        self.params.push(Param::flag(key));
+        self
+    }
+
+    /// Run the operation of this step in reverse (`+inv`)
+    pub fn inv(mut self) -> Self {
+        self.inverse = true;
+        self
+    }
+
+    /// Skip this step when the pipeline is run forwards (`+omit_fwd`)
+    pub fn omit_fwd(mut self) -> Self {
+        self.omit_fwd = true;
+        self
+    }
+
+    /// Skip this step when the pipeline is run in reverse (`+omit_inv`)
+    pub fn omit_inv(mut self) -> Self {
+        self.omit_inv = true;
+        self
+    }
+
+    /// Look up the value of a parameter
+    pub fn get(&self, key: &str) -> Option<&Param> {
+        self.params.iter().find(|param| param.key == key)
+    }
+
+    /// Save coordinate components (`1` to `4`) on the pipeline stack
+    pub fn push(components: &[u8]) -> Self {
+        components
+            .iter()
+            .fold(Step::new("push"), |step, c| step.flag(&format!("v_{}", c)))
+    }
+
+    /// Restore coordinate components (`1` to `4`) from the pipeline stack
+    pub fn pop(components: &[u8]) -> Self {
+        components
+            .iter()
+            .fold(Step::new("pop"), |step, c| step.flag(&format!("v_{}", c)))
+    }
+
+    /// Reorder axes; `order` lists the input axis (`1` to `4`) of each output axis, negated to change its direction
+    pub fn axisswap(order: &[i8]) -> Self {
+        let order = order
+            .iter()
+            .map(|axis| axis.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        Step::new("axisswap").param("order", order)
+    }
+
+    /// Convert the horizontal units of coordinates, e.g. from `"deg"` to `"rad"`
+    pub fn unitconvert(xy_in: &str, xy_out: &str) -> Self {
+        Step::new("unitconvert")
+            .param("xy_in", xy_in)
+            .param("xy_out", xy_out)
+    }
+
+    /// Convert geodetic coordinates to geocentric cartesian coordinates on the given ellipsoid, e.g. `"GRS80"`
+    pub fn cart(ellps: &str) -> Self {
+        Step::new("cart").param("ellps", ellps)
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.inverse {
+            write!(f, "+inv ")?;
+        }
+        write!(f, "+proj={}", self.operation)?;
+        for param in &self.params {
+            write!(f, " {}", param)?;
+        }
+        if self.omit_fwd {
+            write!(f, " +omit_fwd")?;
+        }
+        if self.omit_inv {
+            write!(f, " +omit_inv")?;
+        }
+        Ok(())
+    }
+}
+
+/// A `PROJ` [pipeline](https://proj.org/operations/pipeline.html)
+///
+/// Pipelines can be built step by step, and serialised to a string accepted by [`Proj::new`](struct.Proj.html#method.new):
+///
+/// ```rust
+/// use proj::{Pipeline, Proj, Step};
+/// extern crate geo_types;
+/// use geo_types::Point;
+/// # use assert_approx_eq::assert_approx_eq;
+/// let pipeline = Pipeline::new()
+///     .step(Step::unitconvert("deg", "rad"))
+///     .step(Step::new("utm").param("zone", 32).param("ellps", "GRS80"));
+/// assert_eq!(
+///     pipeline.to_string(),
+///     "+proj=pipeline +step +proj=unitconvert +xy_in=deg +xy_out=rad +step +proj=utm +zone=32 +ellps=GRS80"
+/// );
+/// let utm = Proj::new(&pipeline.to_string()).unwrap();
+/// let t = utm.convert(Point::new(9.0f64, 0.0f64)).unwrap();
+/// assert_approx_eq!(t.x(), 500000.0f64, 1.0e-6);
+/// ```
+///
+/// Definitions, including the output of [`Proj::def`](struct.Proj.html#method.def), can also be parsed back into steps:
+///
+/// ```rust
+/// use proj::Pipeline;
+/// let pipeline: Pipeline = "+proj=pipeline +step +inv +proj=utm +zone=32 +step +proj=utm +zone=33"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(pipeline.steps.len(), 2);
+/// assert!(pipeline.steps[0].inverse);
+/// assert_eq!(pipeline.steps[1].get("zone").unwrap().value, Some("33".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Pipeline {
+    /// Parameters which apply to every step
+    pub global_params: Vec<Param>,
+    /// The steps of the pipeline, in the order they are run forwards
+    pub steps: Vec<Step>,
+}
+
+impl Pipeline {
+    /// Create an empty pipeline
+    pub fn new() -> Self {
+        Pipeline::default()
+    }
+
+    /// Append a step
+    pub fn step<S: Into<Step>>(mut self, step: S) -> Self {
+        self.steps.push(step.into());
+        self
+    }
+
+    /// Add a `key=value` parameter which applies to every step
+    pub fn param<V: ToString>(mut self, key: &str, value: V) -> Self {
+        self.global_params.push(Param::new(key, value));
+        self
+    }
+
+    /// Parse a `PROJ` definition into a pipeline
+    ///
+    /// Definitions consisting of a single operation are parsed into a pipeline with a single step.
+    pub fn parse(definition: &str) -> Result<Pipeline, ProjError> {
+        let params = definition
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<Param>, _>>()?;
+        let is_pipeline = params
+            .iter()
+            .any(|p| p.key == "proj" && p.value.as_deref() == Some("pipeline"));
+        let mut pipeline = Pipeline::new();
+        let mut current: Option<Step> = if is_pipeline {
+            None
+        } else {
+            Some(Step::new(""))
+        };
+        for param in params {
+            match (param.key.as_str(), current.as_mut()) {
+                ("proj", _) if param.value.as_deref() == Some("pipeline") => {}
+                ("step", _) => {
+                    if let Some(step) = current.take() {
+                        pipeline.steps.push(step);
+                    }
+                    current = Some(Step::new(""));
+                }
+                (_, None) => pipeline.global_params.push(param),
+                ("proj", Some(step)) => step.operation = param.value.unwrap_or_default(),
+                ("inv", Some(step)) => step.inverse = true,
+                ("omit_fwd", Some(step)) => step.omit_fwd = true,
+                ("omit_inv", Some(step)) => step.omit_inv = true,
+                (_, Some(step)) => step.params.push(param),
+            }
+        }
+        if let Some(step) = current {
+            pipeline.steps.push(step);
+        }
+        match pipeline.steps.iter().find(|step| step.operation.is_empty()) {
+            Some(_) => Err(ProjError::Pipeline(
+                "every step must specify an operation using proj=".to_string(),
+            )),
+            None => Ok(pipeline),
+        }
+    }
+}
+
+impl fmt::Display for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "+proj=pipeline")?;
+        for param in &self.global_params {
+            write!(f, " {}", param)?;
+        }
+        for step in &self.steps {
+            write!(f, " +step {}", step)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Pipeline {
+    type Err = ProjError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Pipeline::parse(s)
+    }
+}
+
+impl Proj {
+    /// Parse the current definition from `PROJ` into a [`Pipeline`](struct.Pipeline.html)
+    pub fn pipeline(&self) -> Result<Pipeline, ProjError> {
+        Pipeline::parse(&self.def())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Helmert, Pipeline, Proj, RotationConvention, Step};
+    use geo_types::Point;
+
+    fn assert_almost_eq(a: f64, b: f64) {
+        let f: f64 = a / b;
+        assert!(f < 1.00001);
+        assert!(f > 0.99999);
+    }
+
+    fn nad83_ft_to_m() -> Pipeline {
+        let lcc = |units: &str, x_0: f64, y_0: f64| {
+            Step::new("lcc")
+                .param("lat_1", 33.88333333333333)
+                .param("lat_2", 32.78333333333333)
+                .param("lat_0", 32.16666666666666)
+                .param("lon_0", -116.25)
+                .param("x_0", x_0)
+                .param("y_0", y_0)
+                .param("ellps", "GRS80")
+                .param("units", units)
+        };
+        Pipeline::new()
+            .step(lcc("us-ft", 2000000.0001016, 500000.0001016001).inv())
+            .step(lcc("m", 2000000.0, 500000.0))
+    }
+
+    #[test]
+    fn test_build_pipeline() {
+        let nad83_m = Proj::new(&nad83_ft_to_m().to_string()).unwrap();
+        // Presidio, San Francisco
+        let t = nad83_m
+            .convert(Point::new(4760096.421921, 3744293.729449))
+            .unwrap();
+        assert_almost_eq(t.x(), 1450880.29);
+        assert_almost_eq(t.y(), 1141263.01);
+    }
+
+    #[test]
+    fn test_parse_definition() {
+        let pipeline = nad83_ft_to_m();
+        assert_eq!(pipeline.to_string().parse::<Pipeline>().unwrap(), pipeline);
+        let parsed = Proj::new(&pipeline.to_string())
+            .unwrap()
+            .pipeline()
+            .unwrap();
+        assert_eq!(parsed.steps.len(), 2);
+        assert!(parsed.steps[0].inverse);
+        assert!(!parsed.steps[1].inverse);
+        assert_eq!(parsed.steps[1].operation, "lcc");
+        assert_eq!(
+            parsed.steps[0].get("units").unwrap().value,
+            Some("us-ft".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_single_operation() {
+        let parsed = Proj::new("+proj=merc +ellps=WGS84")
+            .unwrap()
+            .pipeline()
+            .unwrap();
+        assert_eq!(parsed.steps.len(), 1);
+        assert_eq!(parsed.steps[0].operation, "merc");
+        assert!(Pipeline::parse("+proj=pipeline +step +ellps=GRS80").is_err());
+    }
+
+    #[test]
+    fn test_helper_steps() {
+        let pipeline = Pipeline::new()
+            .step(Step::axisswap(&[2, 1]))
+            .step(Step::unitconvert("deg", "rad"))
+            .step(Step::cart("GRS80"))
+            .step(Step::push(&[3]))
+            .step(Helmert {
+                translation: [1.0, 2.0, 3.0],
+                rotation: [0.0, 0.0, 0.0],
+                scale: 0.0,
+                convention: RotationConvention::PositionVector,
+            })
+            .step(Step::cart("GRS80").inv())
+            .step(Step::pop(&[3]).omit_inv());
+        assert_eq!(
+            pipeline.to_string(),
+            "+proj=pipeline +step +proj=axisswap +order=2,1 \
+             +step +proj=unitconvert +xy_in=deg +xy_out=rad +step +proj=cart +ellps=GRS80 \
+             +step +proj=push +v_3 +step +proj=helmert +x=1 +y=2 +z=3 +rx=0 +ry=0 +rz=0 +s=0 \
+             +convention=position_vector +step +inv +proj=cart +ellps=GRS80 \
+             +step +proj=pop +v_3 +omit_inv"
+        );
+        assert!(Proj::new(&pipeline.to_string()).is_some());
+    }
+}
@@ -1,16 +1,25 @@
+use crate::coord::Coord;
+use crate::fileapi::FileApiHandler;
+use crate::network::NetworkHandler;
 use geo_types::Point;
 use libc::c_int;
 use libc::{c_char, c_double};
 use num_traits::Float;
 use proj_sys::{
-    proj_area_create, proj_area_destroy, proj_area_set_bbox, proj_context_create,
-    proj_context_destroy, proj_create, proj_create_crs_to_crs, proj_destroy, proj_errno_string,
-    proj_normalize_for_visualization, proj_pj_info, proj_trans, proj_trans_array, PJconsts,
-    PJ_AREA, PJ_CONTEXT, PJ_COORD, PJ_DIRECTION_PJ_FWD, PJ_DIRECTION_PJ_INV, PJ_LP, PJ_XY,
+    proj_angular_input, proj_angular_output, proj_area_create, proj_area_destroy,
+    proj_area_set_bbox, proj_clone, proj_context_create, proj_context_destroy, proj_context_errno,
+    proj_context_set_search_paths, proj_create, proj_create_crs_to_crs,
+    proj_create_crs_to_crs_from_pj, proj_destroy, proj_errno_string, proj_factors,
+    proj_get_source_crs, proj_get_target_crs, proj_get_type, proj_normalize_for_visualization,
+    proj_pj_info, proj_roundtrip, proj_trans, proj_trans_array, PJconsts, PJ_AREA, PJ_CONTEXT,
+    PJ_COORD, PJ_DIRECTION, PJ_DIRECTION_PJ_FWD, PJ_DIRECTION_PJ_INV, PJ_FACTORS, PJ_LP,
+    PJ_TYPE_PJ_TYPE_GEOGRAPHIC_2D_CRS, PJ_TYPE_PJ_TYPE_GEOGRAPHIC_3D_CRS,
+    PJ_TYPE_PJ_TYPE_GEOGRAPHIC_CRS, PJ_XY, PJ_XYZT,
 };
 use proj_sys::{proj_errno, proj_errno_reset};
 use std::ffi::CStr;
 use std::ffi::CString;
+use std::ptr;
 use std::str;
 use thiserror::Error;
 
@@ -21,12 +30,44 @@ pub enum ProjError {
     Projection(String),
     #[error("The conversion failed with the following error: {0}")]
     Conversion(String),
+    #[error("The factor calculation failed with the following error: {0}")]
+    Factors(String),
+    #[error("The geodesic calculation failed with the following error: {0}")]
+    Geodesic(String),
+    #[error("The round trip failed with the following error: {0}")]
+    Roundtrip(String),
+    #[error("Transforming the bounds failed with the following error: {0}")]
+    Bounds(String),
+    #[error("Parsing the pipeline failed with the following error: {0}")]
+    Pipeline(String),
+    #[error("Configuring the context failed with the following error: {0}")]
+    Context(String),
+    #[error("Finding coordinate operations failed with the following error: {0}")]
+    Operations(String),
+    #[error("The grid download failed with the following error: {0}")]
+    Download(String),
+    #[error("The version check failed with the following error: {0}")]
+    Version(String),
+    #[error("Parsing the angle failed with the following error: {0}")]
+    Angle(String),
+    #[error("Creating the transformation between CRSs failed with the following error: {0}")]
+    Crs(String),
+    #[error("Parsing the WKT failed with the following error: {0}")]
+    Wkt(String),
+    #[error("Parsing the WKB failed with the following error: {0}")]
+    Wkb(String),
+    #[error("Querying the database failed with the following error: {0}")]
+    Database(String),
+    #[cfg(feature = "with-geojson")]
+    #[error("Reading or writing GeoJSON failed with the following error: {0}")]
+    GeoJson(String),
 }
 
 /// The bounding box of an area of use
 ///
 /// In the case of an area of use crossing the antimeridian (longitude +/- 180 degrees),
 /// `west` must be greater than `east`.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Area {
     north: f64,
     south: f64,
@@ -47,20 +88,103 @@ impl Area {
             north,
         }
     }
+
+    /// The western boundary
+    pub fn west(&self) -> f64 {
+        self.west
+    }
+
+    /// The southern boundary
+    pub fn south(&self) -> f64 {
+        self.south
+    }
+
+    /// The eastern boundary
+    pub fn east(&self) -> f64 {
+        self.east
+    }
+
+    /// The northern boundary
+    pub fn north(&self) -> f64 {
+        self.north
+    }
+}
+
+/// Scale and distortion factors of a projection at a geodetic location
+///
+/// Angular values are in radians. See the
+/// [PROJ documentation](https://proj.org/development/reference/datatypes.html#c.PJ_FACTORS)
+/// for a full description of each factor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Factors {
+    /// Scale factor along the meridian
+    pub meridional_scale: f64,
+    /// Scale factor along the parallel
+    pub parallel_scale: f64,
+    /// Areal scale factor
+    pub areal_scale: f64,
+    /// Maximum angular distortion
+    pub angular_distortion: f64,
+    /// Angle between the meridian and the parallel
+    pub meridian_parallel_angle: f64,
+    /// Meridian convergence, i.e. the angle between grid north and true north
+    pub meridian_convergence: f64,
+    /// Semi-major axis of the Tissot indicatrix
+    pub tissot_semimajor: f64,
+    /// Semi-minor axis of the Tissot indicatrix
+    pub tissot_semiminor: f64,
+    /// Partial derivative of `x` with respect to longitude
+    pub dx_dlam: f64,
+    /// Partial derivative of `x` with respect to latitude
+    pub dx_dphi: f64,
+    /// Partial derivative of `y` with respect to longitude
+    pub dy_dlam: f64,
+    /// Partial derivative of `y` with respect to latitude
+    pub dy_dphi: f64,
+}
+
+impl From<PJ_FACTORS> for Factors {
+    fn from(f: PJ_FACTORS) -> Self {
+        Factors {
+            meridional_scale: f.meridional_scale,
+            parallel_scale: f.parallel_scale,
+            areal_scale: f.areal_scale,
+            angular_distortion: f.angular_distortion,
+            meridian_parallel_angle: f.meridian_parallel_angle,
+            meridian_convergence: f.meridian_convergence,
+            tissot_semimajor: f.tissot_semimajor,
+            tissot_semiminor: f.tissot_semiminor,
+            dx_dlam: f.dx_dlam,
+            dx_dphi: f.dx_dphi,
+            dy_dlam: f.dy_dlam,
+            dy_dphi: f.dy_dphi,
+        }
+    }
 }
 
 /// Easily get a String from the external library
-fn _string(raw_ptr: *const c_char) -> String {
+pub(crate) fn _string(raw_ptr: *const c_char) -> String {
     let c_str = unsafe { CStr::from_ptr(raw_ptr) };
     str::from_utf8(c_str.to_bytes()).unwrap().to_string()
 }
 
 /// Look up an error message using the error code
-fn error_message(code: c_int) -> String {
+pub(crate) fn error_message(code: c_int) -> String {
     let rv = unsafe { proj_errno_string(code) };
     _string(rv)
 }
 
+/// Look up the last error set on a context, which may not have been set
+pub(crate) fn context_error(ctx: *mut PJ_CONTEXT) -> String {
+    let err = unsafe { proj_context_errno(ctx) };
+    let message = unsafe { proj_errno_string(err) };
+    if message.is_null() {
+        "unknown error".to_string()
+    } else {
+        _string(message)
+    }
+}
+
 /// Set the bounding box of the area of use
 fn area_set_bbox(parea: *mut proj_sys::PJ_AREA, new_area: Option<Area>) {
     // if a bounding box has been passed, modify the proj area object
@@ -71,13 +195,195 @@ fn area_set_bbox(parea: *mut proj_sys::PJ_AREA, new_area: Option<Area>) {
     }
 }
 
+/// State referenced by callbacks installed on a `PROJ` context
+///
+/// This must outlive the context, so it is owned by the `Proj` (or `ProjBuilder`)
+/// holding the context, and is only dropped after the context has been destroyed.
+#[derive(Default)]
+pub(crate) struct Callbacks {
+    pub(crate) file_api: Option<Box<FileApiHandler>>,
+    pub(crate) network: Option<Box<NetworkHandler>>,
+}
+
+/// A builder for `Proj` instances, allowing the `PROJ` context to be configured
+/// before any objects are created from it
+///
+///```rust
+/// extern crate proj;
+/// use proj::ProjBuilder;
+///
+/// let mut builder = ProjBuilder::new();
+/// builder.set_search_paths(&["/usr/share/proj"]);
+/// let ft_to_m = builder
+///     .proj("+proj=pipeline +step +proj=unitconvert +xy_in=us-ft +xy_out=m")
+///     .unwrap();
+/// ```
+pub struct ProjBuilder {
+    pub(crate) ctx: *mut PJ_CONTEXT,
+    pub(crate) callbacks: Callbacks,
+}
+
+impl ProjBuilder {
+    /// Create a new builder with a fresh `PROJ` context
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn new() -> Self {
+        ProjBuilder {
+            ctx: unsafe { proj_context_create() },
+            callbacks: Callbacks::default(),
+        }
+    }
+
+    /// Set the directories in which `PROJ` looks for resource files such as
+    /// `proj.db`, init files and grids
+    ///
+    /// This overrides the default search paths, as well as the `PROJ_LIB` environment variable.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn set_search_paths<S: AsRef<str>>(&mut self, paths: &[S]) {
+        let c_paths: Vec<CString> = paths
+            .iter()
+            .map(|path| CString::new(path.as_ref()).unwrap())
+            .collect();
+        let ptrs: Vec<*const c_char> = c_paths.iter().map(|path| path.as_ptr()).collect();
+        unsafe {
+            proj_context_set_search_paths(self.ctx, ptrs.len() as c_int, ptrs.as_ptr());
+        }
+    }
+
+    /// Hand over the configured context and callback state, leaving the builder empty
+    fn take(&mut self) -> (*mut PJ_CONTEXT, Callbacks) {
+        (
+            std::mem::replace(&mut self.ctx, ptr::null_mut()),
+            std::mem::take(&mut self.callbacks),
+        )
+    }
+
+    /// Try to instantiate a new `PROJ` instance using the configured context
+    ///
+    /// See [`Proj::new()`](struct.Proj.html#method.new) for details.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn proj(mut self, definition: &str) -> Option<Proj> {
+        let c_definition = CString::new(definition.as_bytes()).unwrap();
+        let (ctx, callbacks) = self.take();
+        let new_c_proj = unsafe { proj_create(ctx, c_definition.as_ptr()) };
+        if new_c_proj.is_null() {
+            unsafe { proj_context_destroy(ctx) };
+            None
+        } else {
+            Some(Proj {
+                c_proj: new_c_proj,
+                ctx,
+                area: None,
+                _callbacks: callbacks,
+            })
+        }
+    }
+
+    /// Create a transformation object between two known coordinate reference systems
+    /// using the configured context
+    ///
+    /// See [`Proj::new_known_crs()`](struct.Proj.html#method.new_known_crs) for details.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn proj_known_crs(self, from: &str, to: &str, area: Option<Area>) -> Option<Proj> {
+        let from_c = CString::new(from.as_bytes()).unwrap();
+        let to_c = CString::new(to.as_bytes()).unwrap();
+        self.crs_to_crs(area, |ctx, proj_area| unsafe {
+            proj_create_crs_to_crs(ctx, from_c.as_ptr(), to_c.as_ptr(), proj_area)
+        })
+    }
+
+    /// Create a transformation object between two coordinate reference system objects
+    /// using the configured context
+    ///
+    /// See [`Proj::new_crs_to_crs()`](struct.Proj.html#method.new_crs_to_crs) for details.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn proj_crs_to_crs(self, from: &Proj, to: &Proj, area: Option<Area>) -> Option<Proj> {
+        self.crs_to_crs(area, |ctx, proj_area| unsafe {
+            // the CRSs belong to other contexts, so they are copied into this one
+            let from = proj_clone(ctx, from.c_proj);
+            let to = proj_clone(ctx, to.c_proj);
+            let c_proj = if from.is_null() || to.is_null() {
+                ptr::null_mut()
+            } else {
+                proj_create_crs_to_crs_from_pj(ctx, from, to, proj_area, ptr::null())
+            };
+            proj_destroy(from);
+            proj_destroy(to);
+            c_proj
+        })
+    }
+
+    /// Create a transformation object using `create`, which is given the context and area of use,
+    /// and normalise its axis order
+    fn crs_to_crs<F>(mut self, area: Option<Area>, create: F) -> Option<Proj>
+    where
+        F: FnOnce(*mut PJ_CONTEXT, *mut PJ_AREA) -> *mut PJconsts,
+    {
+        let (ctx, callbacks) = self.take();
+        let proj_area = unsafe { proj_area_create() };
+        area_set_bbox(proj_area, area);
+        let new_c_proj = create(ctx, proj_area);
+        if new_c_proj.is_null() {
+            unsafe {
+                proj_area_destroy(proj_area);
+                proj_context_destroy(ctx);
+            }
+            None
+        } else {
+            // Normalise input and output order to Lon, Lat / Easting Northing by inserting
+            // An axis swap operation if necessary
+            let normalised = unsafe {
+                let normalised = proj_normalize_for_visualization(ctx, new_c_proj);
+                // deallocate stale PJ pointer
+                proj_destroy(new_c_proj);
+                normalised
+            };
+            Some(Proj {
+                c_proj: normalised,
+                ctx,
+                area: Some(proj_area),
+                _callbacks: callbacks,
+            })
+        }
+    }
+}
+
+impl Default for ProjBuilder {
+    fn default() -> Self {
+        ProjBuilder::new()
+    }
+}
+
+impl Drop for ProjBuilder {
+    fn drop(&mut self) {
+        if !self.ctx.is_null() {
+            unsafe { proj_context_destroy(self.ctx) };
+        }
+    }
+}
+
 /// A `PROJ` instance
 pub struct Proj {
-    c_proj: *mut PJconsts,
-    ctx: *mut PJ_CONTEXT,
-    area: Option<*mut PJ_AREA>,
+    pub(crate) c_proj: *mut PJconsts,
+    pub(crate) ctx: *mut PJ_CONTEXT,
+    pub(crate) area: Option<*mut PJ_AREA>,
+    // dropped after the context, once no callbacks can reference it
+    pub(crate) _callbacks: Callbacks,
 }
 
+// Each `Proj` exclusively owns its context, and the callback state is `Send`,
+// so a `Proj` can be moved to another thread (but not shared between threads)
+unsafe impl Send for Proj {}
+
 impl Proj {
     /// Try to instantiate a new `PROJ` instance
     ///
@@ -96,20 +402,7 @@ impl Proj {
     // PJ_LP signals projection of geodetic coordinates, with output being PJ_XY
     // and vice versa, or using PJ_XY for conversion operations
     pub fn new(definition: &str) -> Option<Proj> {
-        let c_definition = CString::new(definition.as_bytes()).unwrap();
-        let ctx = unsafe { proj_context_create() };
-        let new_c_proj = unsafe { proj_create(ctx, c_definition.as_ptr()) };
-        // check for unexpected returned object type
-        // let return_code: i32 = unsafe { proj_get_type(new_c_proj) };
-        if new_c_proj.is_null() {
-            None
-        } else {
-            Some(Proj {
-                c_proj: new_c_proj,
-                ctx,
-                area: None,
-            })
-        }
+        ProjBuilder::new().proj(definition)
     }
 
     /// Create a transformation object that is a pipeline between two known coordinate reference systems.
@@ -154,28 +447,53 @@ impl Proj {
     /// # Safety
     /// This method contains unsafe code.
     pub fn new_known_crs(from: &str, to: &str, area: Option<Area>) -> Option<Proj> {
-        let from_c = CString::new(from.as_bytes()).unwrap();
-        let to_c = CString::new(to.as_bytes()).unwrap();
-        let ctx = unsafe { proj_context_create() };
-        let proj_area = unsafe { proj_area_create() };
-        area_set_bbox(proj_area, area);
-        let new_c_proj =
-            unsafe { proj_create_crs_to_crs(ctx, from_c.as_ptr(), to_c.as_ptr(), proj_area) };
-        if new_c_proj.is_null() {
+        ProjBuilder::new().proj_known_crs(from, to, area)
+    }
+
+    /// Create a transformation object between two coordinate reference system objects,
+    /// e.g. CRSs built using [`GeographicCrs`](struct.GeographicCrs.html) or
+    /// [`ProjectedCrs`](struct.ProjectedCrs.html)
+    ///
+    /// This behaves like [`new_known_crs()`](#method.new_known_crs), including the normalisation of
+    /// the coordinate order, without the CRSs having to be serialised to strings first.
+    ///
+    ///```rust
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// extern crate proj;
+    /// use proj::Proj;
+    ///
+    /// extern crate geo_types;
+    /// use geo_types::Point;
+    ///
+    /// let from = Proj::new("EPSG:2230").unwrap();
+    /// let to = Proj::new("EPSG:26946").unwrap();
+    /// let nad_ft_to_m = Proj::new_crs_to_crs(&from, &to, None).unwrap();
+    /// let result = nad_ft_to_m
+    ///     .convert(Point::new(4760096.421921f64, 3744293.729449f64))
+    ///     .unwrap();
+    /// assert_approx_eq!(result.x(), 1450880.29f64, 1.0e-2);
+    /// assert_approx_eq!(result.y(), 1141263.01f64, 1.0e-2);
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn new_crs_to_crs(from: &Proj, to: &Proj, area: Option<Area>) -> Option<Proj> {
+        ProjBuilder::new().proj_crs_to_crs(from, to, area)
+    }
+
+    /// Wrap a newly-created PROJ object and its context
+    ///
+    /// If the object is null, the context is destroyed and `None` is returned.
+    pub(crate) fn from_raw(ctx: *mut PJ_CONTEXT, c_proj: *mut PJconsts) -> Option<Proj> {
+        if c_proj.is_null() {
+            unsafe { proj_context_destroy(ctx) };
             None
         } else {
-            // Normalise input and output order to Lon, Lat / Easting Northing by inserting
-            // An axis swap operation if necessary
-            let normalised = unsafe {
-                let normalised = proj_normalize_for_visualization(ctx, new_c_proj);
-                // deallocate stale PJ pointer
-                proj_destroy(new_c_proj);
-                normalised
-            };
             Some(Proj {
-                c_proj: normalised,
+                c_proj,
                 ctx,
-                area: Some(proj_area),
+                area: None,
+                _callbacks: Callbacks::default(),
             })
         }
     }
@@ -256,6 +574,84 @@ impl Proj {
         }
     }
 
+    /// Calculate the scale and distortion factors of the projection at a geodetic location (in radians)
+    ///
+    /// This is only meaningful for projections, i.e. operations taking geodetic input and
+    /// producing projected output. Other operations will return a [`ProjError::Factors`](enum.ProjError.html#variant.Factors).
+    ///
+    /// ```rust
+    /// use proj::Proj;
+    /// extern crate geo_types;
+    /// use geo_types::Point;
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// let merc = Proj::new("+proj=merc +ellps=WGS84").unwrap();
+    /// let factors = merc.factors(Point::new(0.0f64, 0.0f64)).unwrap();
+    /// assert_approx_eq!(factors.meridional_scale, 1.0);
+    /// assert_approx_eq!(factors.parallel_scale, 1.0);
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn factors<T, U>(&self, point: T) -> Result<Factors, ProjError>
+    where
+        T: Into<Point<U>>,
+        U: Float,
+    {
+        self.check_projection()?;
+        let _point: Point<U> = point.into();
+        self.lp_factors(_point.x().to_f64().unwrap(), _point.y().to_f64().unwrap())
+    }
+
+    /// Calculate the scale and distortion factors of the projection for a slice of geodetic locations (in radians)
+    ///
+    /// See [`factors`](#method.factors) for details.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn factors_array<T>(&self, points: &[Point<T>]) -> Result<Vec<Factors>, ProjError>
+    where
+        T: Float,
+    {
+        self.check_projection()?;
+        points
+            .iter()
+            .map(|point| self.lp_factors(point.x().to_f64().unwrap(), point.y().to_f64().unwrap()))
+            .collect()
+    }
+
+    /// Ensure that the operation takes geodetic input and produces projected output
+    fn check_projection(&self) -> Result<(), ProjError> {
+        let (angular_in, angular_out) = unsafe {
+            (
+                proj_angular_input(self.c_proj, PJ_DIRECTION_PJ_FWD),
+                proj_angular_output(self.c_proj, PJ_DIRECTION_PJ_FWD),
+            )
+        };
+        if angular_in == 1 && angular_out == 0 {
+            Ok(())
+        } else {
+            Err(ProjError::Factors(
+                "the operation is not a projection from geodetic coordinates".to_string(),
+            ))
+        }
+    }
+
+    fn lp_factors(&self, lam: c_double, phi: c_double) -> Result<Factors, ProjError> {
+        let factors;
+        let err;
+        let coords = PJ_LP { lam, phi };
+        unsafe {
+            proj_errno_reset(self.c_proj);
+            factors = proj_factors(self.c_proj, PJ_COORD { lp: coords });
+            err = proj_errno(self.c_proj);
+        }
+        if err == 0 {
+            Ok(factors.into())
+        } else {
+            Err(ProjError::Factors(error_message(err)))
+        }
+    }
+
     /// Convert projected coordinates between coordinate reference systems.
     ///
     /// Input and output CRS may be specified in two ways:
@@ -335,7 +731,10 @@ impl Proj {
         }
     }
 
-    /// Convert a mutable slice (or anything that can deref into a mutable slice) of `Point`s
+    /// Convert a mutable slice (or anything that can deref into a mutable slice) of coordinates
+    ///
+    /// Any type implementing [`Coord`](trait.Coord.html) may be used, e.g. `Point`, `Coordinate`,
+    /// or 2D and 3D tuples and arrays.
     ///
     /// The following example converts from NAD83 US Survey Feet (EPSG 2230) to NAD83 Metres (EPSG 26946)
     ///
@@ -365,47 +764,13 @@ impl Proj {
     ///
     /// # Safety
     /// This method contains unsafe code.
-    // TODO: there may be a way of avoiding some allocations, but transmute won't work because
-    // PJ_COORD and Point<T> are different sizes
-    pub fn convert_array<'a, T>(
-        &self,
-        points: &'a mut [Point<T>],
-    ) -> Result<&'a mut [Point<T>], ProjError>
+    pub fn convert_array<'a, C, T>(&self, points: &'a mut [C]) -> Result<&'a mut [C], ProjError>
     where
+        C: Coord<T>,
         T: Float,
     {
-        let err;
-        let trans;
-        // we need PJ_COORD to convert
-        let mut pj = points
-            .iter()
-            .map(|point| {
-                let c_x: c_double = point.x().to_f64().unwrap();
-                let c_y: c_double = point.y().to_f64().unwrap();
-                PJ_COORD {
-                    xy: PJ_XY { x: c_x, y: c_y },
-                }
-            })
-            .collect::<Vec<_>>();
-        pj.shrink_to_fit();
-        unsafe {
-            proj_errno_reset(self.c_proj);
-            trans = proj_trans_array(self.c_proj, PJ_DIRECTION_PJ_FWD, pj.len(), pj.as_mut_ptr());
-            err = proj_errno(self.c_proj);
-        }
-        if err == 0 && trans == 0 {
-            unsafe {
-                // re-fill original slice with Points
-                // feels a bit clunky, but we're guaranteed that pj and points have the same length
-                pj.iter().enumerate().for_each(|(i, coord)| {
-                    points[i] =
-                        Point::new(T::from(coord.xy.x).unwrap(), T::from(coord.xy.y).unwrap())
-                });
-                Ok(points)
-            }
-        } else {
-            Err(ProjError::Projection(error_message(err)))
-        }
+        self.trans_array_coords(points, PJ_DIRECTION_PJ_FWD)
+            .map_err(|err| ProjError::Projection(error_message(err)))
     }
 
     /// Project an array of geodetic coordinates (in radians) into the projection specified by `definition`
@@ -413,6 +778,9 @@ impl Proj {
     /// **Note:** specifying `inverse` as `true` carries out an inverse projection *to* geodetic coordinates
     /// (in radians) from the projection specified by `definition`.
     ///
+    /// Any type implementing [`Coord`](trait.Coord.html) may be used, e.g. `Point`, `Coordinate`,
+    /// or 2D and 3D tuples and arrays.
+    ///
     /// ```rust
     /// use proj::Proj;
     /// extern crate geo_types;
@@ -432,61 +800,675 @@ impl Proj {
     ///
     /// # Safety
     /// This method contains unsafe code.
-    // TODO: there may be a way of avoiding some allocations, but transmute won't work because
-    // PJ_COORD and Point<T> are different sizes
-    pub fn project_array<'a, T>(
+    pub fn project_array<'a, C, T>(
         &self,
-        points: &'a mut [Point<T>],
+        points: &'a mut [C],
         inverse: bool,
-    ) -> Result<&'a mut [Point<T>], ProjError>
+    ) -> Result<&'a mut [C], ProjError>
     where
+        C: Coord<T>,
         T: Float,
     {
-        let err;
-        let trans;
-        let inv = if inverse {
-            PJ_DIRECTION_PJ_INV
-        } else {
-            PJ_DIRECTION_PJ_FWD
-        };
-        // we need PJ_COORD to convert
+        self.trans_array_coords(points, direction(inverse))
+            .map_err(|err| ProjError::Projection(error_message(err)))
+    }
+
+    // TODO: there may be a way of avoiding some allocations, but transmute won't work because
+    // PJ_COORD and the coordinate types are different sizes
+    fn trans_array_coords<'a, C, T>(
+        &self,
+        points: &'a mut [C],
+        direction: PJ_DIRECTION,
+    ) -> Result<&'a mut [C], c_int>
+    where
+        C: Coord<T>,
+        T: Float,
+    {
+        // 2D coordinates are transformed with a zero z component, and without a time: an infinite
+        // time coordinate signals to PROJ that no time is specified
         let mut pj = points
             .iter()
             .map(|point| {
-                let c_x: c_double = point.x().to_f64().unwrap();
-                let c_y: c_double = point.y().to_f64().unwrap();
-                PJ_COORD {
-                    xy: PJ_XY { x: c_x, y: c_y },
-                }
+                xyz(
+                    (point.x(), point.y(), point.z().unwrap_or_else(T::zero)),
+                    f64::INFINITY,
+                )
             })
             .collect::<Vec<_>>();
-        pj.shrink_to_fit();
+        self.trans_pj_array(&mut pj, direction)?;
+        pj.iter().zip(points.iter_mut()).for_each(|(coord, point)| {
+            let (x, y, z) = from_xyz(*coord);
+            point.set_xy(x, y);
+            point.set_z(z);
+        });
+        Ok(points)
+    }
+
+    /// Transform coordinates in place using `proj_trans_array`
+    ///
+    /// On failure, the result is the `PROJ` error number, or the return value of
+    /// `proj_trans_array` if no error number was set.
+    fn trans_pj_array(&self, pj: &mut [PJ_COORD], direction: PJ_DIRECTION) -> Result<(), c_int> {
+        let err;
+        let trans;
         unsafe {
             proj_errno_reset(self.c_proj);
-            trans = proj_trans_array(self.c_proj, inv, pj.len(), pj.as_mut_ptr());
+            trans = proj_trans_array(self.c_proj, direction, pj.len(), pj.as_mut_ptr());
             err = proj_errno(self.c_proj);
         }
         if err == 0 && trans == 0 {
-            unsafe {
-                // re-fill original slice with Points
-                // feels a bit clunky, but we're guaranteed that pj and points have the same length
-                pj.iter().enumerate().for_each(|(i, coord)| {
-                    points[i] =
-                        Point::new(T::from(coord.xy.x).unwrap(), T::from(coord.xy.y).unwrap())
-                });
-                Ok(points)
-            }
+            Ok(())
+        } else if err != 0 {
+            Err(err)
         } else {
-            Err(ProjError::Projection(error_message(err)))
+            Err(trans)
         }
     }
-}
 
-impl Drop for Proj {
-    fn drop(&mut self) {
-        unsafe {
-            proj_destroy(self.c_proj);
-            proj_context_destroy(self.ctx);
+    /// Convert three-dimensional coordinates between coordinate reference systems
+    ///
+    /// This behaves like [`convert`](#method.convert), but the third (height) component of `point` is
+    /// passed to `PROJ` and transformed, instead of being dropped. This allows e.g. transformations between
+    /// compound horizontal + vertical CRS such as `"EPSG:26910+5703"`, in which heights are converted
+    /// using geoid models, if the required grids are available.
+    ///
+    /// ```rust
+    /// use proj::Proj;
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// // WGS 84 (3D) -> WGS 84 (geocentric)
+    /// let to_geocentric = Proj::new_known_crs("EPSG:4979", "EPSG:4978", None).unwrap();
+    /// let (x, y, z) = to_geocentric.convert_3d((0.0f64, 0.0f64, 100.0f64)).unwrap();
+    /// assert_approx_eq!(x, 6378237.0f64, 1.0e-6);
+    /// assert_approx_eq!(y, 0.0f64, 1.0e-6);
+    /// assert_approx_eq!(z, 0.0f64, 1.0e-6);
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn convert_3d<U>(&self, point: (U, U, U)) -> Result<(U, U, U), ProjError>
+    where
+        U: Float,
+    {
+        // an infinite time coordinate signals to PROJ that no time is specified
+        self.trans_3d(point, PJ_DIRECTION_PJ_FWD, f64::INFINITY)
+            .map_err(|err| ProjError::Conversion(error_message(err)))
+    }
+
+    /// Convert a mutable slice of three-dimensional coordinates between coordinate reference systems
+    ///
+    /// See [`convert_3d`](#method.convert_3d) for details.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn convert_array_3d<'a, U>(
+        &self,
+        points: &'a mut [(U, U, U)],
+    ) -> Result<&'a mut [(U, U, U)], ProjError>
+    where
+        U: Float,
+    {
+        // an infinite time coordinate signals to PROJ that no time is specified
+        self.trans_array_3d(points, PJ_DIRECTION_PJ_FWD, f64::INFINITY)
+            .map_err(|err| ProjError::Conversion(error_message(err)))
+    }
+
+    /// Convert three-dimensional coordinates observed at an epoch between coordinate reference systems
+    ///
+    /// The time component of `point` is the observation epoch, as a decimal year (e.g. `2020.5`), and is used by
+    /// time-dependent transformations such as those between dynamic datums (e.g. ITRF2014 -> ETRF2000).
+    /// It is otherwise returned unchanged.
+    ///
+    /// **Note**: Coordinate epochs can't be attached to CRS objects with the version of `PROJ` used by this crate:
+    /// they must be specified for each point.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn convert_4d<U>(&self, point: (U, U, U, U)) -> Result<(U, U, U, U), ProjError>
+    where
+        U: Float,
+    {
+        let trans;
+        let err;
+        unsafe {
+            proj_errno_reset(self.c_proj);
+            trans = proj_trans(self.c_proj, PJ_DIRECTION_PJ_FWD, xyzt(point));
+            err = proj_errno(self.c_proj);
+        }
+        if err == 0 {
+            Ok(from_xyzt(trans))
+        } else {
+            Err(ProjError::Conversion(error_message(err)))
+        }
+    }
+
+    /// Convert a mutable slice of three-dimensional coordinates, each observed at its own epoch
+    ///
+    /// See [`convert_4d`](#method.convert_4d) for details.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn convert_array_4d<'a, U>(
+        &self,
+        points: &'a mut [(U, U, U, U)],
+    ) -> Result<&'a mut [(U, U, U, U)], ProjError>
+    where
+        U: Float,
+    {
+        let mut pj = points.iter().map(|point| xyzt(*point)).collect::<Vec<_>>();
+        self.trans_pj_array(&mut pj, PJ_DIRECTION_PJ_FWD)
+            .map_err(|err| ProjError::Conversion(error_message(err)))?;
+        pj.iter()
+            .zip(points.iter_mut())
+            .for_each(|(coord, point)| *point = from_xyzt(*coord));
+        Ok(points)
+    }
+
+    /// Convert a mutable slice of three-dimensional coordinates, all observed at the same `epoch`
+    ///
+    /// `epoch` is a decimal year, e.g. `2020.5`. See [`convert_4d`](#method.convert_4d) for details.
+    ///
+    /// ```rust
+    /// use proj::Proj;
+    /// // ITRF2014 (geocentric) -> ETRF2000 (geocentric)
+    /// let itrf_to_etrf = Proj::new_known_crs("EPSG:7789", "EPSG:7930", None).unwrap();
+    /// let mut v = vec![(4027894.006f64, 307045.600f64, 4919474.910f64)];
+    /// itrf_to_etrf.convert_array_3d_at_epoch(&mut v, 2020.5).unwrap();
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn convert_array_3d_at_epoch<'a, U>(
+        &self,
+        points: &'a mut [(U, U, U)],
+        epoch: f64,
+    ) -> Result<&'a mut [(U, U, U)], ProjError>
+    where
+        U: Float,
+    {
+        self.trans_array_3d(points, PJ_DIRECTION_PJ_FWD, epoch)
+            .map_err(|err| ProjError::Conversion(error_message(err)))
+    }
+
+    /// Carry out a three-dimensional operation, or its inverse
+    ///
+    /// This behaves like [`project`](#method.project), but the third component of `point` is
+    /// passed to `PROJ` and transformed, instead of being dropped.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn project_3d<U>(&self, point: (U, U, U), inverse: bool) -> Result<(U, U, U), ProjError>
+    where
+        U: Float,
+    {
+        // an infinite time coordinate signals to PROJ that no time is specified
+        self.trans_3d(point, direction(inverse), f64::INFINITY)
+            .map_err(|err| ProjError::Projection(error_message(err)))
+    }
+
+    /// Carry out a three-dimensional operation, or its inverse, on a mutable slice of coordinates
+    ///
+    /// See [`project_3d`](#method.project_3d) for details.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn project_array_3d<'a, U>(
+        &self,
+        points: &'a mut [(U, U, U)],
+        inverse: bool,
+    ) -> Result<&'a mut [(U, U, U)], ProjError>
+    where
+        U: Float,
+    {
+        // an infinite time coordinate signals to PROJ that no time is specified
+        self.trans_array_3d(points, direction(inverse), f64::INFINITY)
+            .map_err(|err| ProjError::Projection(error_message(err)))
+    }
+
+    fn trans_3d<U>(
+        &self,
+        point: (U, U, U),
+        direction: PJ_DIRECTION,
+        t: f64,
+    ) -> Result<(U, U, U), c_int>
+    where
+        U: Float,
+    {
+        let trans;
+        let err;
+        let coords = xyz(point, t);
+        unsafe {
+            proj_errno_reset(self.c_proj);
+            trans = proj_trans(self.c_proj, direction, coords);
+            err = proj_errno(self.c_proj);
+        }
+        if err == 0 {
+            Ok(from_xyz(trans))
+        } else {
+            Err(err)
+        }
+    }
+
+    fn trans_array_3d<'a, U>(
+        &self,
+        points: &'a mut [(U, U, U)],
+        direction: PJ_DIRECTION,
+        t: f64,
+    ) -> Result<&'a mut [(U, U, U)], c_int>
+    where
+        U: Float,
+    {
+        let mut pj = points
+            .iter()
+            .map(|point| xyz(*point, t))
+            .collect::<Vec<_>>();
+        self.trans_pj_array(&mut pj, direction)?;
+        pj.iter()
+            .zip(points.iter_mut())
+            .for_each(|(coord, point)| *point = from_xyz(*coord));
+        Ok(points)
+    }
+
+    /// Measure the numerical drift of `n` forward and inverse round trips, starting from `point`
+    ///
+    /// The result is the distance, in metres, between `point` and the coordinate obtained after the round trips.
+    /// `inverse` selects the direction of the first half of each round trip, as for [`project`](#method.project):
+    /// for a projection, `false` means that `point` is geodetic (in radians), and `true` that it's projected.
+    /// `n` is capped at `i32::MAX`.
+    ///
+    /// ```rust
+    /// use proj::Proj;
+    /// extern crate geo_types;
+    /// use geo_types::Point;
+    /// let merc = Proj::new("+proj=merc +ellps=WGS84").unwrap();
+    /// let drift = merc.roundtrip(Point::new(0.1f64, 0.7f64), 100, false).unwrap();
+    /// assert!(drift < 1.0e-6);
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn roundtrip<T, U>(&self, point: T, n: usize, inverse: bool) -> Result<f64, ProjError>
+    where
+        T: Into<Point<U>>,
+        U: Float,
+    {
+        let _point: Point<U> = point.into();
+        self.xy_roundtrip(
+            _point.x().to_f64().unwrap(),
+            _point.y().to_f64().unwrap(),
+            n,
+            inverse,
+        )
+    }
+
+    /// Measure the numerical drift of `n` forward and inverse round trips for each of a slice of `Point`s
+    ///
+    /// See [`roundtrip`](#method.roundtrip) for details.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn roundtrip_array<T>(
+        &self,
+        points: &[Point<T>],
+        n: usize,
+        inverse: bool,
+    ) -> Result<Vec<f64>, ProjError>
+    where
+        T: Float,
+    {
+        points
+            .iter()
+            .map(|point| {
+                self.xy_roundtrip(
+                    point.x().to_f64().unwrap(),
+                    point.y().to_f64().unwrap(),
+                    n,
+                    inverse,
+                )
+            })
+            .collect()
+    }
+
+    fn xy_roundtrip(
+        &self,
+        x: c_double,
+        y: c_double,
+        n: usize,
+        inverse: bool,
+    ) -> Result<f64, ProjError> {
+        let drift;
+        let err;
+        // an infinite time coordinate signals to PROJ that no time is specified
+        let mut coord = xyz((x, y, 0.0), f64::INFINITY);
+        // PROJ counts the round trips with a C int
+        let n = n.min(c_int::MAX as usize) as c_int;
+        unsafe {
+            proj_errno_reset(self.c_proj);
+            drift = proj_roundtrip(self.c_proj, direction(inverse), n, &mut coord);
+            err = proj_errno(self.c_proj);
+        }
+        if err == 0 {
+            Ok(drift)
+        } else {
+            Err(ProjError::Roundtrip(error_message(err)))
+        }
+    }
+
+    /// Transform a bounding box, densifying its edges to account for curvature
+    ///
+    /// Each edge of `bounds` is densified with `densify_pts` additional points, which are transformed along with
+    /// the corners; the result is the bounding box of all successfully transformed points.
+    /// Using only the corners (i.e. `densify_pts` = `0`) will generally underestimate the extent of the output
+    /// for curved projections and polar regions. `21` is a reasonable default.
+    ///
+    /// Bounds are specified in the units of the source and target CRS, i.e. in degrees for geographic CRS
+    /// created using [`new_known_crs`](#method.new_known_crs), whose coordinate order is **normalised**.
+    /// If the source CRS is geographic, `west` may be greater than `east` to indicate
+    /// that the bounding box crosses the antimeridian. If the target CRS is geographic and the transformed
+    /// points cross the antimeridian, the resulting `west` will be greater than `east`. If a pole lies within
+    /// `bounds` (e.g. those of a polar stereographic projection), the resulting extent reaches that pole,
+    /// at all longitudes.
+    ///
+    /// Returns a [`ProjError::Bounds`](enum.ProjError.html#variant.Bounds) if none of the points can be transformed.
+    ///
+    /// ```rust
+    /// use proj::{Area, Proj};
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// let from = "EPSG:4326";
+    /// let to = "EPSG:3857";
+    /// let to_merc = Proj::new_known_crs(&from, &to, None).unwrap();
+    /// let bounds = to_merc
+    ///     .transform_bounds(Area::new(-10.0, 40.0, 10.0, 60.0), 21)
+    ///     .unwrap();
+    /// assert_approx_eq!(bounds.west(), -1113194.9079327357f64, 1.0e-2);
+    /// assert_approx_eq!(bounds.north(), 8399737.889818357f64, 1.0e-2);
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn transform_bounds(&self, bounds: Area, densify_pts: usize) -> Result<Area, ProjError> {
+        let (source_geographic, target_geographic) = self.geographic_endpoints();
+        let mut east = bounds.east;
+        if source_geographic && bounds.west > bounds.east {
+            east += 360.0;
+        }
+        let segments = densify_pts + 1;
+        let edge = |from: (f64, f64), to: (f64, f64)| {
+            (0..segments).map(move |i| {
+                let f = i as f64 / segments as f64;
+                let x = from.0 + (to.0 - from.0) * f;
+                let y = from.1 + (to.1 - from.1) * f;
+                if source_geographic && x > 180.0 {
+                    (x - 360.0, y)
+                } else {
+                    (x, y)
+                }
+            })
+        };
+        let (sw, se) = ((bounds.west, bounds.south), (east, bounds.south));
+        let (ne, nw) = ((east, bounds.north), (bounds.west, bounds.north));
+        let transformed = edge(sw, se)
+            .chain(edge(se, ne))
+            .chain(edge(ne, nw))
+            .chain(edge(nw, sw))
+            .filter_map(|(x, y)| self.convert(Point::new(x, y)).ok())
+            .filter(|p: &Point<f64>| p.x().is_finite() && p.y().is_finite())
+            .collect::<Vec<_>>();
+        if transformed.is_empty() {
+            return Err(ProjError::Bounds(
+                "none of the points of the bounding box could be transformed".to_string(),
+            ));
+        }
+        let mut south = transformed.iter().map(|p| p.y()).fold(f64::MAX, f64::min);
+        let mut north = transformed.iter().map(|p| p.y()).fold(f64::MIN, f64::max);
+        let mut xs = transformed.iter().map(|p| p.x()).collect::<Vec<_>>();
+        xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let (mut west, mut east) = if target_geographic {
+            antimeridian_extent(&xs)
+        } else {
+            (xs[0], xs[xs.len() - 1])
+        };
+        // a pole inside the bounds (e.g. of a polar stereographic projection) isn't reached by
+        // the densified edges, but extends the geographic extent to that pole, at all longitudes
+        if target_geographic {
+            if self.contains_pole(bounds, source_geographic, 90.0) {
+                north = 90.0;
+                west = -180.0;
+                east = 180.0;
+            }
+            if self.contains_pole(bounds, source_geographic, -90.0) {
+                south = -90.0;
+                west = -180.0;
+                east = 180.0;
+            }
+        }
+        Ok(Area::new(west, south, east, north))
+    }
+
+    /// Check whether the pole at `latitude` (in the geographic target CRS) lies within `bounds`
+    /// (in the source CRS)
+    fn contains_pole(&self, bounds: Area, source_geographic: bool, latitude: f64) -> bool {
+        // an infinite time coordinate signals to PROJ that no time is specified
+        let (mut x, y, _) =
+            match self.trans_3d((0.0, latitude, 0.0), PJ_DIRECTION_PJ_INV, f64::INFINITY) {
+                Ok(pole) if pole.0.is_finite() && pole.1.is_finite() => pole,
+                _ => return false,
+            };
+        let mut east = bounds.east;
+        if source_geographic && bounds.west > bounds.east {
+            east += 360.0;
+            if x < bounds.west {
+                x += 360.0;
+            }
+        }
+        bounds.west <= x && x <= east && bounds.south <= y && y <= bounds.north
+    }
+
+    /// Check whether the source and target CRS of the operation are geographic
+    fn geographic_endpoints(&self) -> (bool, bool) {
+        unsafe {
+            let source = proj_get_source_crs(self.ctx, self.c_proj);
+            let target = proj_get_target_crs(self.ctx, self.c_proj);
+            let geographic = (is_geographic(source), is_geographic(target));
+            for crs in [source, target].iter().filter(|crs| !crs.is_null()) {
+                proj_destroy(*crs);
+            }
+            geographic
+        }
+    }
+
+    /// Check whether the operation expects angular input coordinates (in radians)
+    ///
+    /// `inverse` selects the direction of the operation, as for [`project`](#method.project).
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn angular_input(&self, inverse: bool) -> bool {
+        unsafe { proj_angular_input(self.c_proj, direction(inverse)) == 1 }
+    }
+
+    /// Check whether the operation produces angular output coordinates (in radians)
+    ///
+    /// `inverse` selects the direction of the operation, as for [`project`](#method.project).
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn angular_output(&self, inverse: bool) -> bool {
+        unsafe { proj_angular_output(self.c_proj, direction(inverse)) == 1 }
+    }
+
+    /// Project geodetic coordinates (in degrees) into the projection specified by `definition`
+    ///
+    /// This behaves like [`project`](#method.project), but geodetic coordinates are given and returned in degrees:
+    /// input is converted to radians if the operation expects [angular input](#method.angular_input),
+    /// and output is converted to degrees if the operation produces [angular output](#method.angular_output).
+    ///
+    /// ```rust
+    /// use proj::Proj;
+    /// extern crate geo_types;
+    /// use geo_types::Point;
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// let stereo70 = Proj::new(
+    ///     "+proj=sterea +lat_0=46 +lon_0=25 +k=0.99975 +x_0=500000 +y_0=500000
+    ///     +ellps=krass +towgs84=33.4,-146.6,-76.3,-0.359,-0.053,0.844,-0.84 +units=m +no_defs",
+    /// )
+    /// .unwrap();
+    /// // Geodetic -> Pulkovo 1942(58) / Stereo70 (EPSG 3844)
+    /// // (the same point as in the `project` example, given in degrees)
+    /// let lon = 0.436332f64.to_degrees();
+    /// let lat = 0.802851f64.to_degrees();
+    /// let t = stereo70
+    ///     .project_degrees(Point::new(lon, lat), false)
+    ///     .unwrap();
+    /// assert_approx_eq!(t.x(), 500119.7035366755f64, 1.0e-2);
+    /// assert_approx_eq!(t.y(), 500027.77901023754f64, 1.0e-2);
+    /// // and back again
+    /// let g = stereo70.project_degrees(t, true).unwrap();
+    /// assert_approx_eq!(g.x(), lon, 1.0e-6);
+    /// assert_approx_eq!(g.y(), lat, 1.0e-6);
+    /// ```
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn project_degrees<T, U>(&self, point: T, inverse: bool) -> Result<Point<U>, ProjError>
+    where
+        T: Into<Point<U>>,
+        U: Float,
+    {
+        let mut _point: Point<U> = point.into();
+        if self.angular_input(inverse) {
+            _point = to_radians(_point);
+        }
+        let projected = self.project(_point, inverse)?;
+        if self.angular_output(inverse) {
+            Ok(to_degrees(projected))
+        } else {
+            Ok(projected)
+        }
+    }
+
+    /// Project an array of geodetic coordinates (in degrees) into the projection specified by `definition`
+    ///
+    /// See [`project_degrees`](#method.project_degrees) for details of angular unit handling.
+    /// If the projection fails, `points` are left unchanged.
+    ///
+    /// # Safety
+    /// This method contains unsafe code.
+    pub fn project_array_degrees<'a, C, T>(
+        &self,
+        points: &'a mut [C],
+        inverse: bool,
+    ) -> Result<&'a mut [C], ProjError>
+    where
+        C: Coord<T>,
+        T: Float,
+    {
+        // keep the input, so that it can be restored if the projection fails
+        let mut original = vec![];
+        if self.angular_input(inverse) {
+            original = points.iter().map(|p| (p.x(), p.y())).collect();
+            points
+                .iter_mut()
+                .for_each(|p| p.set_xy(p.x().to_radians(), p.y().to_radians()));
+        }
+        if let Err(err) = self.project_array(&mut *points, inverse) {
+            points
+                .iter_mut()
+                .zip(original)
+                .for_each(|(p, (x, y))| p.set_xy(x, y));
+            return Err(err);
+        }
+        if self.angular_output(inverse) {
+            points
+                .iter_mut()
+                .for_each(|p| p.set_xy(p.x().to_degrees(), p.y().to_degrees()));
+        }
+        Ok(points)
+    }
+}
+
+/// Check whether a (possibly null) object is a geographic CRS
+unsafe fn is_geographic(crs: *mut PJconsts) -> bool {
+    !crs.is_null()
+        && matches!(
+            proj_get_type(crs),
+            PJ_TYPE_PJ_TYPE_GEOGRAPHIC_CRS
+                | PJ_TYPE_PJ_TYPE_GEOGRAPHIC_2D_CRS
+                | PJ_TYPE_PJ_TYPE_GEOGRAPHIC_3D_CRS
+        )
+}
+
+/// Calculate the smallest west-east extent containing the (sorted) longitudes, in degrees
+///
+/// If the extent crosses the antimeridian, `west` will be greater than `east`.
+fn antimeridian_extent(lons: &[f64]) -> (f64, f64) {
+    let n = lons.len();
+    // the gap which wraps around the antimeridian
+    let mut widest = (lons[0] + 360.0 - lons[n - 1], lons[0], lons[n - 1]);
+    for pair in lons.windows(2) {
+        let gap = pair[1] - pair[0];
+        if gap > widest.0 {
+            widest = (gap, pair[1], pair[0]);
+        }
+    }
+    (widest.1, widest.2)
+}
+
+fn xyzt<U: Float>(point: (U, U, U, U)) -> PJ_COORD {
+    PJ_COORD {
+        xyzt: PJ_XYZT {
+            x: point.0.to_f64().unwrap(),
+            y: point.1.to_f64().unwrap(),
+            z: point.2.to_f64().unwrap(),
+            t: point.3.to_f64().unwrap(),
+        },
+    }
+}
+
+fn from_xyzt<U: Float>(coord: PJ_COORD) -> (U, U, U, U) {
+    let xyzt = unsafe { coord.xyzt };
+    (
+        U::from(xyzt.x).unwrap(),
+        U::from(xyzt.y).unwrap(),
+        U::from(xyzt.z).unwrap(),
+        U::from(xyzt.t).unwrap(),
+    )
+}
+
+// Build a coordinate from a 3D point and its time coordinate
+fn xyz<U: Float>(point: (U, U, U), t: f64) -> PJ_COORD {
+    xyzt((point.0, point.1, point.2, U::from(t).unwrap()))
+}
+
+fn from_xyz<U: Float>(coord: PJ_COORD) -> (U, U, U) {
+    let xyz = unsafe { coord.xyz };
+    (
+        U::from(xyz.x).unwrap(),
+        U::from(xyz.y).unwrap(),
+        U::from(xyz.z).unwrap(),
+    )
+}
+
+fn direction(inverse: bool) -> PJ_DIRECTION {
+    if inverse {
+        PJ_DIRECTION_PJ_INV
+    } else {
+        PJ_DIRECTION_PJ_FWD
+    }
+}
+
+fn to_radians<T: Float>(point: Point<T>) -> Point<T> {
+    Point::new(point.x().to_radians(), point.y().to_radians())
+}
+
+fn to_degrees<T: Float>(point: Point<T>) -> Point<T> {
+    Point::new(point.x().to_degrees(), point.y().to_degrees())
+}
+
+impl Drop for Proj {
+    fn drop(&mut self) {
+        unsafe {
+            proj_destroy(self.c_proj);
+            proj_context_destroy(self.ctx);
             if let Some(area) = self.area {
                 proj_area_destroy(area)
             }
@@ -496,8 +1478,9 @@ impl Drop for Proj {
 
 #[cfg(test)]
 mod test {
-    use super::Proj;
-    use geo_types::Point;
+    use super::{Area, Proj};
+    use geo_types::{Coordinate, LineString, Point};
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
 
     fn assert_almost_eq(a: f64, b: f64) {
         let f: f64 = a / b;
@@ -648,6 +1631,35 @@ mod test {
         assert_almost_eq(v[1].y(), 1141293.7960220212f64);
     }
 
+    #[test]
+    fn test_array_convert_coordinate_types() {
+        let ft_to_m = Proj::new_known_crs("EPSG:2230", "EPSG:26946", None).unwrap();
+        let mut tuples = vec![(4760096.421921, 3744293.729449)];
+        ft_to_m.convert_array(&mut tuples).unwrap();
+        assert_almost_eq(tuples[0].0, 1450880.2910605003);
+        assert_almost_eq(tuples[0].1, 1141263.01);
+        let mut arrays = vec![[4760096.421921, 3744293.729449]];
+        ft_to_m.convert_array(&mut arrays).unwrap();
+        assert_eq!(arrays[0], [tuples[0].0, tuples[0].1]);
+        let mut line = LineString::from(vec![(4760096.421921, 3744293.729449)]);
+        ft_to_m.convert_array(&mut line.0).unwrap();
+        assert_eq!(line.0[0], Coordinate::from(tuples[0]));
+    }
+
+    #[test]
+    fn test_array_project_3d_coordinates() {
+        let cart = Proj::new("+proj=cart +ellps=WGS84").unwrap();
+        let mut v = vec![[0.0, 0.0, 100.0], [0.0, FRAC_PI_2, 0.0]];
+        cart.project_array(&mut v, false).unwrap();
+        assert_almost_eq(v[0][0], 6378237.0);
+        assert!(v[0][1].abs() < 1e-6);
+        assert!(v[0][2].abs() < 1e-6);
+        // the polar radius of WGS84
+        assert_almost_eq(v[1][2], 6356752.314245179);
+        cart.project_array(&mut v, true).unwrap();
+        assert_almost_eq(v[0][2], 100.0);
+    }
+
     #[test]
     // Ensure that input and output order are normalised to Lon, Lat / Easting Northing
     // Without normalisation this test would fail, as EPSG:4326 expects Lat, Lon input order.
@@ -661,4 +1673,170 @@ mod test {
         assert_eq!(6693625.67217475, usa_ft.x());
         assert_eq!(3497301.5918027186, usa_ft.y());
     }
+
+    #[test]
+    fn test_factors() {
+        let merc = Proj::new("+proj=merc +ellps=WGS84").unwrap();
+        // the Mercator projection is conformal, so both scales are equal away from the equator
+        let f = merc.factors(Point::new(0.0, FRAC_PI_4)).unwrap();
+        assert_almost_eq(f.meridional_scale, f.parallel_scale);
+        assert_almost_eq(f.meridional_scale, 1.411844757758394);
+        assert!(f.angular_distortion.abs() < 1e-6);
+        let v = vec![Point::new(0.0, 0.0), Point::new(0.0, FRAC_PI_4)];
+        let fs = merc.factors_array(&v).unwrap();
+        assert_eq!(fs.len(), 2);
+        assert_almost_eq(fs[0].areal_scale, 1.0);
+    }
+
+    #[test]
+    fn test_factors_not_a_projection() {
+        let from = "EPSG:2230";
+        let to = "EPSG:26946";
+        let ft_to_m = Proj::new_known_crs(from, to, None).unwrap();
+        let err = ft_to_m.factors(Point::new(0.0, 0.0)).unwrap_err();
+        assert_eq!(
+            "The factor calculation failed with the following error: the operation is not a projection from geodetic coordinates",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_angular_units() {
+        let stereo70 = Proj::new(
+            "+proj=sterea +lat_0=46 +lon_0=25 +k=0.99975 +x_0=500000 +y_0=500000
+            +ellps=krass +towgs84=33.4,-146.6,-76.3,-0.359,-0.053,0.844,-0.84 +units=m +no_defs",
+        )
+        .unwrap();
+        assert!(stereo70.angular_input(false));
+        assert!(!stereo70.angular_output(false));
+        assert!(!stereo70.angular_input(true));
+        assert!(stereo70.angular_output(true));
+        // the same point as in test_projection, given in degrees
+        let (lon, lat) = (0.436332f64.to_degrees(), 0.802851f64.to_degrees());
+        let mut v = vec![Point::new(lon, lat)];
+        stereo70.project_array_degrees(&mut v, false).unwrap();
+        assert_almost_eq(v[0].x(), 500119.7035366755);
+        assert_almost_eq(v[0].y(), 500027.77901023754);
+        stereo70.project_array_degrees(&mut v, true).unwrap();
+        assert_almost_eq(v[0].x(), lon);
+        assert_almost_eq(v[0].y(), lat);
+    }
+
+    #[test]
+    fn test_project_array_degrees_failure() {
+        let merc = Proj::new("+proj=merc +ellps=WGS84").unwrap();
+        // the second point is beyond the pole, so it can't be projected
+        let mut v = vec![Point::new(10.0, 45.0), Point::new(10.0, 100.0)];
+        assert!(merc.project_array_degrees(&mut v, false).is_err());
+        assert_eq!(v, vec![Point::new(10.0, 45.0), Point::new(10.0, 100.0)]);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let osgb36 = Proj::new(
+            "
+            +proj=tmerc +lat_0=49 +lon_0=-2 +k=0.9996012717 +x_0=400000 +y_0=-100000 +ellps=airy
+            +towgs84=446.448,-125.157,542.06,0.15,0.247,0.842,-20.489 +units=m +no_defs
+            ",
+        )
+        .unwrap();
+        let drift = osgb36
+            .roundtrip(Point::new(548295.39, 182498.46), 100, true)
+            .unwrap();
+        assert!(drift < 1e-6);
+        let drifts = osgb36
+            .roundtrip_array(
+                &[Point::new(0.0023755864848281206, 0.8992274896304518)],
+                100,
+                false,
+            )
+            .unwrap();
+        assert_eq!(drifts.len(), 1);
+        assert!(drifts[0] < 1e-6);
+    }
+
+    #[test]
+    fn test_transform_bounds() {
+        let to_merc = Proj::new_known_crs("EPSG:4326", "EPSG:3857", None).unwrap();
+        let bounds = to_merc
+            .transform_bounds(Area::new(-10.0, 40.0, 10.0, 60.0), 21)
+            .unwrap();
+        assert_almost_eq(bounds.west(), -1113194.9079327357);
+        assert_almost_eq(bounds.south(), 4865942.279503176);
+        assert_almost_eq(bounds.east(), 1113194.9079327357);
+        assert_almost_eq(bounds.north(), 8399737.889818357);
+    }
+
+    #[test]
+    fn test_transform_bounds_antimeridian() {
+        let identity = Proj::new_known_crs("EPSG:4326", "EPSG:4326", None).unwrap();
+        let bounds = identity
+            .transform_bounds(Area::new(170.0, -50.0, -170.0, -30.0), 21)
+            .unwrap();
+        assert_almost_eq(bounds.west(), 170.0);
+        assert_almost_eq(bounds.east(), -170.0);
+        assert_almost_eq(bounds.south(), -50.0);
+        assert_almost_eq(bounds.north(), -30.0);
+    }
+
+    #[test]
+    fn test_transform_bounds_pole() {
+        // NSIDC Sea Ice Polar Stereographic North -> WGS 84
+        let to_wgs84 = Proj::new_known_crs("EPSG:3413", "EPSG:4326", None).unwrap();
+        let bounds = to_wgs84
+            .transform_bounds(Area::new(-1000000.0, -1000000.0, 1000000.0, 1000000.0), 21)
+            .unwrap();
+        // the north pole is inside the bounds, but not on their edges
+        assert_eq!(bounds.north(), 90.0);
+        assert_eq!(bounds.west(), -180.0);
+        assert_eq!(bounds.east(), 180.0);
+        assert!(bounds.south() > 70.0 && bounds.south() < 80.0);
+        // bounds which don't contain the pole are unaffected
+        let bounds = to_wgs84
+            .transform_bounds(Area::new(1000000.0, 1000000.0, 2000000.0, 2000000.0), 21)
+            .unwrap();
+        assert!(bounds.north() < 90.0);
+        assert!(bounds.east() - bounds.west() < 90.0);
+    }
+
+    #[test]
+    fn test_convert_3d() {
+        // WGS 84 (3D) -> WGS 84 (geocentric): heights must not be dropped
+        let to_geocentric = Proj::new_known_crs("EPSG:4979", "EPSG:4978", None).unwrap();
+        let t = to_geocentric.convert_3d((0.0f64, 90.0, 100.0)).unwrap();
+        assert!(t.0.abs() < 1e-6);
+        assert!(t.1.abs() < 1e-6);
+        assert_almost_eq(t.2, 6356752.314245179 + 100.0);
+        let mut v = vec![(0.0, 0.0, 0.0), (0.0, 0.0, 100.0)];
+        to_geocentric.convert_array_3d(&mut v).unwrap();
+        assert_almost_eq(v[1].0 - v[0].0, 100.0);
+    }
+
+    #[test]
+    fn test_coordinate_epoch() {
+        // ITRF2014 (geocentric) -> ETRF2000 (geocentric), using a time-dependent Helmert transformation
+        let itrf_to_etrf = Proj::new_known_crs("EPSG:7789", "EPSG:7930", None).unwrap();
+        let station = (4027894.006f64, 307045.600, 4919474.910);
+        let at_2000 = itrf_to_etrf
+            .convert_4d((station.0, station.1, station.2, 2000.0))
+            .unwrap();
+        let at_2020 = itrf_to_etrf
+            .convert_4d((station.0, station.1, station.2, 2020.5))
+            .unwrap();
+        assert_eq!(at_2020.3, 2020.5);
+        // the Eurasian plate moves by a few centimetres per year in ITRF
+        let drift = ((at_2020.0 - at_2000.0).powi(2)
+            + (at_2020.1 - at_2000.1).powi(2)
+            + (at_2020.2 - at_2000.2).powi(2))
+        .sqrt();
+        assert!(drift > 0.1 && drift < 2.0);
+        let mut v = vec![station];
+        itrf_to_etrf
+            .convert_array_3d_at_epoch(&mut v, 2020.5)
+            .unwrap();
+        assert_almost_eq(v[0].0, at_2020.0);
+        let mut v = vec![(station.0, station.1, station.2, 2020.5)];
+        itrf_to_etrf.convert_array_4d(&mut v).unwrap();
+        assert_almost_eq(v[0].2, at_2020.2);
+    }
 }
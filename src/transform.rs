@@ -0,0 +1,384 @@
+use crate::proj::{Proj, ProjError};
+use geo_types::{
+    Coordinate, Geometry, GeometryCollection, Line, LineString, MultiLineString, MultiPoint,
+    MultiPolygon, Point, Polygon, Rect, Triangle,
+};
+use num_traits::Float;
+use std::slice;
+
+/// Transform a geometry in place using a `Proj` instance
+///
+/// `transform_in_place` applies the operation in the forward direction, as
+/// [`Proj::convert`](struct.Proj.html#method.convert) and `Proj::project(point, false)` do;
+/// `transform_inverse_in_place` applies it in the inverse direction, as `Proj::project(point, true)` does.
+/// As with [`Proj::project`](struct.Proj.html#method.project), geodetic coordinates are
+/// expected in radians unless the operation specifies otherwise, e.g. those created by
+/// [`Proj::new_known_crs`](struct.Proj.html#method.new_known_crs).
+///
+/// All the coordinates of a geometry are transformed in a single batch. If the transformation fails,
+/// the geometry is left unchanged.
+///
+/// **Note:** a `Rect` is transformed using its four corners only, and the result is the `Rect` spanning
+/// the transformed corners. Use [`Proj::transform_bounds`](struct.Proj.html#method.transform_bounds)
+/// to account for curved edges.
+///
+///```rust
+/// # use assert_approx_eq::assert_approx_eq;
+/// extern crate proj;
+/// use proj::{Proj, TransformInPlace};
+///
+/// extern crate geo_types;
+/// use geo_types::{Geometry, Point};
+///
+/// let ft_to_m = Proj::new_known_crs("EPSG:2230", "EPSG:26946", None).unwrap();
+/// let mut geometry = Geometry::Point(Point::new(4760096.421921f64, 3744293.729449f64));
+/// geometry.transform_in_place(&ft_to_m).unwrap();
+/// let result = geometry.into_point().unwrap();
+/// assert_approx_eq!(result.x(), 1450880.29, 1e-2);
+/// assert_approx_eq!(result.y(), 1141263.01, 1e-2);
+/// ```
+pub trait TransformInPlace<T: Float> {
+    /// Transform the geometry in place, in the forward direction
+    fn transform_in_place(&mut self, proj: &Proj) -> Result<(), ProjError>;
+    /// Transform the geometry in place, in the inverse direction
+    fn transform_inverse_in_place(&mut self, proj: &Proj) -> Result<(), ProjError>;
+}
+
+/// Transform a copy of a geometry using a `Proj` instance
+///
+/// See [`TransformInPlace`](trait.TransformInPlace.html) for the direction semantics.
+///
+///```rust
+/// # use assert_approx_eq::assert_approx_eq;
+/// extern crate proj;
+/// use proj::{Proj, Transform};
+///
+/// extern crate geo_types;
+/// use geo_types::{LineString, Polygon};
+///
+/// let ft_to_m = Proj::new_known_crs("EPSG:2230", "EPSG:26946", None).unwrap();
+/// let polygon = Polygon::new(
+///     LineString::from(vec![
+///         (4760096.421921f64, 3744293.729449f64),
+///         (4760196.421921, 3744293.729449),
+///         (4760196.421921, 3744393.729449),
+///     ]),
+///     vec![],
+/// );
+/// let result = polygon.transform(&ft_to_m).unwrap();
+/// assert_approx_eq!(result.exterior().0[0].x, 1450880.29, 1e-2);
+/// ```
+pub trait Transform<T: Float>: Sized {
+    /// Return a transformed copy of the geometry, in the forward direction
+    fn transform(&self, proj: &Proj) -> Result<Self, ProjError>;
+    /// Return a transformed copy of the geometry, in the inverse direction
+    fn transform_inverse(&self, proj: &Proj) -> Result<Self, ProjError>;
+}
+
+impl<T, G> Transform<T> for G
+where
+    T: Float,
+    G: TransformInPlace<T> + Clone,
+{
+    fn transform(&self, proj: &Proj) -> Result<Self, ProjError> {
+        let mut transformed = self.clone();
+        transformed.transform_in_place(proj)?;
+        Ok(transformed)
+    }
+
+    fn transform_inverse(&self, proj: &Proj) -> Result<Self, ProjError> {
+        let mut transformed = self.clone();
+        transformed.transform_inverse_in_place(proj)?;
+        Ok(transformed)
+    }
+}
+
+// Visits the coordinates of a geometry as mutable slices
+trait Coords<T: Float> {
+    fn coords_mut(&mut self, f: &mut dyn FnMut(&mut [Coordinate<T>]));
+}
+
+impl<T: Float> Coords<T> for Coordinate<T> {
+    fn coords_mut(&mut self, f: &mut dyn FnMut(&mut [Coordinate<T>])) {
+        f(slice::from_mut(self))
+    }
+}
+
+impl<T: Float> Coords<T> for Point<T> {
+    fn coords_mut(&mut self, f: &mut dyn FnMut(&mut [Coordinate<T>])) {
+        f(slice::from_mut(&mut self.0))
+    }
+}
+
+impl<T: Float> Coords<T> for Line<T> {
+    fn coords_mut(&mut self, f: &mut dyn FnMut(&mut [Coordinate<T>])) {
+        let mut coords = [self.start, self.end];
+        f(&mut coords);
+        self.start = coords[0];
+        self.end = coords[1];
+    }
+}
+
+impl<T: Float> Coords<T> for LineString<T> {
+    fn coords_mut(&mut self, f: &mut dyn FnMut(&mut [Coordinate<T>])) {
+        f(&mut self.0)
+    }
+}
+
+impl<T: Float> Coords<T> for Polygon<T> {
+    fn coords_mut(&mut self, f: &mut dyn FnMut(&mut [Coordinate<T>])) {
+        self.exterior_mut(|exterior| f(&mut exterior.0));
+        self.interiors_mut(|interiors| {
+            interiors.iter_mut().for_each(|interior| f(&mut interior.0))
+        });
+    }
+}
+
+impl<T: Float> Coords<T> for MultiPoint<T> {
+    fn coords_mut(&mut self, f: &mut dyn FnMut(&mut [Coordinate<T>])) {
+        self.0.iter_mut().for_each(|point| point.coords_mut(f))
+    }
+}
+
+impl<T: Float> Coords<T> for MultiLineString<T> {
+    fn coords_mut(&mut self, f: &mut dyn FnMut(&mut [Coordinate<T>])) {
+        self.0.iter_mut().for_each(|line| line.coords_mut(f))
+    }
+}
+
+impl<T: Float> Coords<T> for MultiPolygon<T> {
+    fn coords_mut(&mut self, f: &mut dyn FnMut(&mut [Coordinate<T>])) {
+        self.0.iter_mut().for_each(|polygon| polygon.coords_mut(f))
+    }
+}
+
+impl<T: Float> Coords<T> for Rect<T> {
+    fn coords_mut(&mut self, f: &mut dyn FnMut(&mut [Coordinate<T>])) {
+        let (min, max) = (self.min, self.max);
+        let mut corners = [
+            min,
+            Coordinate { x: max.x, y: min.y },
+            max,
+            Coordinate { x: min.x, y: max.y },
+        ];
+        f(&mut corners);
+        // a rotated or skewed rect may have any of its transformed corners at its extremes
+        let (min, max) = corners[1..]
+            .iter()
+            .fold((corners[0], corners[0]), |(min, max), c| {
+                (
+                    Coordinate {
+                        x: min.x.min(c.x),
+                        y: min.y.min(c.y),
+                    },
+                    Coordinate {
+                        x: max.x.max(c.x),
+                        y: max.y.max(c.y),
+                    },
+                )
+            });
+        *self = Rect::new(min, max);
+    }
+}
+
+impl<T: Float> Coords<T> for Triangle<T> {
+    fn coords_mut(&mut self, f: &mut dyn FnMut(&mut [Coordinate<T>])) {
+        let mut vertices = self.to_array();
+        f(&mut vertices);
+        *self = Triangle(vertices[0], vertices[1], vertices[2]);
+    }
+}
+
+impl<T: Float> Coords<T> for Geometry<T> {
+    fn coords_mut(&mut self, f: &mut dyn FnMut(&mut [Coordinate<T>])) {
+        match self {
+            Geometry::Point(g) => g.coords_mut(f),
+            Geometry::Line(g) => g.coords_mut(f),
+            Geometry::LineString(g) => g.coords_mut(f),
+            Geometry::Polygon(g) => g.coords_mut(f),
+            Geometry::MultiPoint(g) => g.coords_mut(f),
+            Geometry::MultiLineString(g) => g.coords_mut(f),
+            Geometry::MultiPolygon(g) => g.coords_mut(f),
+            Geometry::GeometryCollection(g) => g.coords_mut(f),
+        }
+    }
+}
+
+impl<T: Float> Coords<T> for GeometryCollection<T> {
+    fn coords_mut(&mut self, f: &mut dyn FnMut(&mut [Coordinate<T>])) {
+        self.0
+            .iter_mut()
+            .for_each(|geometry| geometry.coords_mut(f))
+    }
+}
+
+/// Transform all the coordinates of a geometry as a single batch
+fn transform_coords<T, G>(geometry: &mut G, proj: &Proj, inverse: bool) -> Result<(), ProjError>
+where
+    T: Float,
+    G: Coords<T>,
+{
+    let mut points = vec![];
+    geometry.coords_mut(&mut |coords| points.extend_from_slice(coords));
+    proj.project_array(&mut points, inverse)?;
+    let mut transformed = points.into_iter();
+    geometry.coords_mut(&mut |coords| {
+        coords
+            .iter_mut()
+            .zip(&mut transformed)
+            .for_each(|(coord, transformed)| *coord = transformed)
+    });
+    Ok(())
+}
+
+macro_rules! impl_transform {
+    ($($geometry:ident),*) => {
+        $(
+            impl<T: Float> TransformInPlace<T> for $geometry<T> {
+                fn transform_in_place(&mut self, proj: &Proj) -> Result<(), ProjError> {
+                    transform_coords(self, proj, false)
+                }
+
+                fn transform_inverse_in_place(&mut self, proj: &Proj) -> Result<(), ProjError> {
+                    transform_coords(self, proj, true)
+                }
+            }
+        )*
+    };
+}
+
+impl_transform!(
+    Coordinate,
+    Point,
+    Line,
+    LineString,
+    Polygon,
+    MultiPoint,
+    MultiLineString,
+    MultiPolygon,
+    Rect,
+    Triangle,
+    Geometry,
+    GeometryCollection
+);
+
+#[cfg(test)]
+mod test {
+    use super::{Transform, TransformInPlace};
+    use crate::proj::Proj;
+    use geo_types::{
+        Coordinate, Geometry, GeometryCollection, Line, LineString, MultiPoint, Point, Polygon,
+        Rect,
+    };
+
+    fn assert_almost_eq(a: f64, b: f64) {
+        let f: f64 = a / b;
+        assert!(f < 1.00001);
+        assert!(f > 0.99999);
+    }
+
+    fn ft_to_m() -> Proj {
+        Proj::new_known_crs("EPSG:2230", "EPSG:26946", None).unwrap()
+    }
+
+    #[test]
+    fn test_transform_polygon() {
+        let polygon = Polygon::new(
+            LineString::from(vec![
+                (4760096.421921, 3744293.729449),
+                (4760196.421921, 3744293.729449),
+                (4760196.421921, 3744393.729449),
+            ]),
+            vec![LineString::from(vec![
+                (4760100.0, 3744300.0),
+                (4760110.0, 3744300.0),
+                (4760110.0, 3744310.0),
+            ])],
+        );
+        let result = polygon.transform(&ft_to_m()).unwrap();
+        let exterior = &result.exterior().0;
+        assert_eq!(exterior.len(), 4);
+        assert_almost_eq(exterior[0].x, 1450880.29);
+        assert_almost_eq(exterior[0].y, 1141263.01);
+        // 100 US survey feet
+        assert_almost_eq(exterior[1].x - exterior[0].x, 30.48006);
+        assert_eq!(exterior[0], exterior[3]);
+        assert_eq!(result.interiors()[0].0.len(), 4);
+        // the original is untouched
+        assert_eq!(polygon.exterior().0[0].x, 4760096.421921);
+    }
+
+    #[test]
+    fn test_transform_geometry_collection_in_place() {
+        let mut collection = GeometryCollection(vec![
+            Geometry::Point(Point::new(4760096.421921, 3744293.729449)),
+            Geometry::MultiPoint(MultiPoint(vec![
+                Point::new(4760096.421921, 3744293.729449),
+                Point::new(4760096.421921, 3744293.729449),
+            ])),
+            Geometry::Line(Line::new(
+                Coordinate {
+                    x: 4760096.421921,
+                    y: 3744293.729449,
+                },
+                Coordinate {
+                    x: 4760196.421921,
+                    y: 3744393.729449,
+                },
+            )),
+        ]);
+        collection.transform_in_place(&ft_to_m()).unwrap();
+        match &collection.0[1] {
+            Geometry::MultiPoint(points) => {
+                for point in &points.0 {
+                    assert_almost_eq(point.x(), 1450880.29);
+                    assert_almost_eq(point.y(), 1141263.01);
+                }
+            }
+            _ => panic!("unexpected geometry"),
+        }
+        match &collection.0[2] {
+            Geometry::Line(line) => {
+                assert_almost_eq(line.start.x, 1450880.29);
+                assert_almost_eq(line.end.x - line.start.x, 30.48006);
+            }
+            _ => panic!("unexpected geometry"),
+        }
+    }
+
+    #[test]
+    fn test_transform_rect_corners() {
+        // the meridians converge towards the pole, so the south-east corner is the easternmost
+        let utm = Proj::new_known_crs("EPSG:4326", "EPSG:32633", None).unwrap();
+        let rect = Rect::new(
+            Coordinate { x: 10.0, y: 40.0 },
+            Coordinate { x: 20.0, y: 50.0 },
+        );
+        let result = rect.transform(&utm).unwrap();
+        let south_east = utm.convert(Point::new(20.0, 40.0)).unwrap();
+        let north_east = utm.convert(Point::new(20.0, 50.0)).unwrap();
+        assert!(south_east.x() > north_east.x());
+        assert_almost_eq(result.max.x, south_east.x());
+        assert_almost_eq(result.max.y, north_east.y());
+    }
+
+    #[test]
+    fn test_transform_inverse() {
+        let merc = Proj::new("+proj=merc +ellps=WGS84").unwrap();
+        let point = Point::new(0.1f64, 0.5f64);
+        let projected = point.transform(&merc).unwrap();
+        let projected_directly = merc.project(point, false).unwrap();
+        assert_eq!(projected, projected_directly);
+        let roundtripped = projected.transform_inverse(&merc).unwrap();
+        assert_almost_eq(roundtripped.x(), 0.1);
+        assert_almost_eq(roundtripped.y(), 0.5);
+    }
+
+    #[test]
+    fn test_transform_failure_leaves_geometry_unchanged() {
+        let merc = Proj::new("+proj=merc +ellps=WGS84").unwrap();
+        let mut line = LineString::from(vec![(0.1, 0.5), (0.1, 100.0)]);
+        assert!(line.transform_in_place(&merc).is_err());
+        assert_eq!(line, LineString::from(vec![(0.1, 0.5), (0.1, 100.0)]));
+    }
+}
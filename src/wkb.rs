@@ -0,0 +1,403 @@
+use crate::proj::{Proj, ProjError};
+
+// EWKB flags, set in the geometry type
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+/// The location of a coordinate within a WKB geometry
+struct CoordRef {
+    offset: usize,
+    little_endian: bool,
+    has_z: bool,
+}
+
+/// The maximum nesting depth of multi-geometries and collections, which bounds the parser's recursion
+const MAX_DEPTH: usize = 32;
+
+/// A WKB parser, recording the location of every coordinate and SRID
+struct Parser<'a> {
+    wkb: &'a [u8],
+    pos: usize,
+    coords: Vec<CoordRef>,
+    srids: Vec<(usize, bool)>,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ProjError> {
+        let bytes = self
+            .wkb
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| ProjError::Wkb("unexpected end of input".to_string()))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self, little_endian: bool) -> Result<u32, ProjError> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn coords(&mut self, little_endian: bool, has_z: bool, dims: usize) -> Result<(), ProjError> {
+        let count = self.read_u32(little_endian)?;
+        for _ in 0..count {
+            self.coord(little_endian, has_z, dims)?;
+        }
+        Ok(())
+    }
+
+    fn coord(&mut self, little_endian: bool, has_z: bool, dims: usize) -> Result<(), ProjError> {
+        self.coords.push(CoordRef {
+            offset: self.pos,
+            little_endian,
+            has_z,
+        });
+        self.take(8 * dims).map(|_| ())
+    }
+
+    fn geometry(&mut self) -> Result<(), ProjError> {
+        let little_endian = match self.take(1)?[0] {
+            0 => false,
+            1 => true,
+            order => return Err(ProjError::Wkb(format!("invalid byte order {}", order))),
+        };
+        let geometry_type = self.read_u32(little_endian)?;
+        if geometry_type & EWKB_SRID != 0 {
+            self.srids.push((self.pos, little_endian));
+            self.take(4)?;
+        }
+        // ISO WKB encodes the dimensions in the thousands, EWKB in flags
+        let code = geometry_type & 0x0fff_ffff;
+        let has_z = geometry_type & EWKB_Z != 0 || code / 1000 == 1 || code / 1000 == 3;
+        let has_m = geometry_type & EWKB_M != 0 || code / 1000 == 2 || code / 1000 == 3;
+        let dims = 2 + has_z as usize + has_m as usize;
+        match code % 1000 {
+            // Point
+            1 => self.coord(little_endian, has_z, dims),
+            // LineString
+            2 => self.coords(little_endian, has_z, dims),
+            // Polygon, Triangle
+            3 | 17 => {
+                let rings = self.read_u32(little_endian)?;
+                for _ in 0..rings {
+                    self.coords(little_endian, has_z, dims)?;
+                }
+                Ok(())
+            }
+            // MultiPoint, MultiLineString, MultiPolygon, GeometryCollection,
+            // PolyhedralSurface, TIN
+            4..=7 | 15 | 16 => {
+                if self.depth == MAX_DEPTH {
+                    return Err(ProjError::Wkb(format!(
+                        "geometries are nested more than {} deep",
+                        MAX_DEPTH
+                    )));
+                }
+                self.depth += 1;
+                let count = self.read_u32(little_endian)?;
+                for _ in 0..count {
+                    self.geometry()?;
+                }
+                self.depth -= 1;
+                Ok(())
+            }
+            _ => Err(ProjError::Wkb(format!(
+                "unsupported geometry type {}",
+                geometry_type
+            ))),
+        }
+    }
+}
+
+fn read_f64(wkb: &[u8], offset: usize, little_endian: bool) -> f64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&wkb[offset..offset + 8]);
+    if little_endian {
+        f64::from_le_bytes(bytes)
+    } else {
+        f64::from_be_bytes(bytes)
+    }
+}
+
+fn write_f64(wkb: &mut [u8], offset: usize, little_endian: bool, value: f64) {
+    let bytes = if little_endian {
+        value.to_le_bytes()
+    } else {
+        value.to_be_bytes()
+    };
+    wkb[offset..offset + 8].copy_from_slice(&bytes);
+}
+
+fn write_u32(wkb: &mut [u8], offset: usize, little_endian: bool, value: u32) {
+    let bytes = if little_endian {
+        value.to_le_bytes()
+    } else {
+        value.to_be_bytes()
+    };
+    wkb[offset..offset + 4].copy_from_slice(&bytes);
+}
+
+/// The EPSG code of the target CRS of `proj`, for use as an SRID
+pub(crate) fn target_srid(proj: &Proj) -> Result<u32, ProjError> {
+    proj.target_epsg_code()
+        .ok_or_else(|| ProjError::Crs("the target CRS has no EPSG code".to_string()))
+}
+
+/// Reproject a WKB or EWKB geometry
+///
+/// All the coordinates are transformed in the forward direction using `proj`, as
+/// [`Proj::convert`](struct.Proj.html#method.convert) does, and the result is written using the
+/// same byte order and flavour (ISO WKB or EWKB) as the input. `z` coordinates are transformed
+/// along with `x` and `y`; `m` values are left unchanged, as are empty points.
+///
+/// If the input is EWKB with an SRID, the SRID is replaced by the EPSG code of the target CRS of
+/// `proj` (see [`Proj::target_epsg_code`](struct.Proj.html#method.target_epsg_code)), and
+/// reprojection fails if it has none.
+///
+/// Points, line strings, polygons, their multi-part counterparts, geometry collections,
+/// triangles, TINs and polyhedral surfaces are supported. Multi-part geometries and collections
+/// may be nested at most 32 deep.
+///
+///```rust
+/// # use assert_approx_eq::assert_approx_eq;
+/// extern crate proj;
+/// use proj::{reproject_wkb, Proj};
+///
+/// let ft_to_m = Proj::new_known_crs("EPSG:2230", "EPSG:26946", None).unwrap();
+/// // EWKB POINT(4760096.421921 3744293.729449) with SRID 2230, little-endian
+/// let mut ewkb = vec![1, 1, 0, 0, 0x20];
+/// ewkb.extend_from_slice(&2230u32.to_le_bytes());
+/// ewkb.extend_from_slice(&4760096.421921f64.to_le_bytes());
+/// ewkb.extend_from_slice(&3744293.729449f64.to_le_bytes());
+///
+/// let result = reproject_wkb(&ft_to_m, &ewkb).unwrap();
+/// assert_eq!(&result[5..9], &26946u32.to_le_bytes());
+/// let mut x = [0; 8];
+/// x.copy_from_slice(&result[9..17]);
+/// assert_approx_eq!(f64::from_le_bytes(x), 1450880.29, 1e-2);
+/// ```
+pub fn reproject_wkb(proj: &Proj, wkb: &[u8]) -> Result<Vec<u8>, ProjError> {
+    reproject(proj, wkb, None)
+}
+
+/// Reproject a WKB or EWKB geometry, setting its SRID
+///
+/// As [`reproject_wkb`](fn.reproject_wkb.html), but the result is EWKB with the given SRID if
+/// `srid` is `Some`, and has no SRID otherwise.
+pub fn reproject_wkb_with_srid(
+    proj: &Proj,
+    wkb: &[u8],
+    srid: Option<u32>,
+) -> Result<Vec<u8>, ProjError> {
+    reproject(proj, wkb, Some(srid))
+}
+
+fn reproject(proj: &Proj, wkb: &[u8], srid: Option<Option<u32>>) -> Result<Vec<u8>, ProjError> {
+    let mut parser = Parser {
+        wkb,
+        pos: 0,
+        coords: vec![],
+        srids: vec![],
+        depth: 0,
+    };
+    parser.geometry()?;
+    if parser.pos != wkb.len() {
+        return Err(ProjError::Wkb(
+            "unexpected data after the geometry".to_string(),
+        ));
+    }
+    // the SRID to write, and whether the top-level geometry has one
+    let has_srid = parser.srids.first().map(|(offset, _)| *offset) == Some(5);
+    let srid = match srid {
+        Some(srid) => srid,
+        None if has_srid => Some(target_srid(proj)?),
+        None => None,
+    };
+
+    // empty points are encoded as NaN coordinates, and are left unchanged
+    let read =
+        |coord: &CoordRef, i: usize| read_f64(wkb, coord.offset + 8 * i, coord.little_endian);
+    let coords: Vec<&CoordRef> = parser
+        .coords
+        .iter()
+        .filter(|coord| !read(coord, 0).is_nan() && !read(coord, 1).is_nan())
+        .collect();
+    let mut points: Vec<[f64; 3]> = coords
+        .iter()
+        .map(|coord| {
+            let z = if coord.has_z { read(coord, 2) } else { 0.0 };
+            [read(coord, 0), read(coord, 1), z]
+        })
+        .collect();
+    proj.convert_array(&mut points)?;
+
+    let mut result = wkb.to_vec();
+    for (coord, point) in coords.iter().zip(&points) {
+        let dims = if coord.has_z { 3 } else { 2 };
+        for (i, &value) in point.iter().take(dims).enumerate() {
+            write_f64(
+                &mut result,
+                coord.offset + 8 * i,
+                coord.little_endian,
+                value,
+            );
+        }
+    }
+    if let Some(srid) = srid {
+        for &(offset, little_endian) in &parser.srids {
+            write_u32(&mut result, offset, little_endian, srid);
+        }
+    }
+    set_srid(result, has_srid, srid)
+}
+
+/// Add, remove or keep the SRID of the top-level geometry
+fn set_srid(mut wkb: Vec<u8>, has_srid: bool, srid: Option<u32>) -> Result<Vec<u8>, ProjError> {
+    let little_endian = wkb[0] == 1;
+    let mut type_bytes = [0; 4];
+    type_bytes.copy_from_slice(&wkb[1..5]);
+    let geometry_type = if little_endian {
+        u32::from_le_bytes(type_bytes)
+    } else {
+        u32::from_be_bytes(type_bytes)
+    };
+    match (has_srid, srid) {
+        (true, Some(_)) | (false, None) => Ok(wkb),
+        (true, None) => {
+            write_u32(&mut wkb, 1, little_endian, geometry_type & !EWKB_SRID);
+            wkb.drain(5..9);
+            Ok(wkb)
+        }
+        (false, Some(srid)) => {
+            // EWKB encodes the dimensions as flags, rather than ISO WKB's type codes
+            let code = geometry_type & 0x0fff_ffff;
+            let mut ewkb_type = (geometry_type & (EWKB_Z | EWKB_M)) | (code % 1000) | EWKB_SRID;
+            if code / 1000 == 1 || code / 1000 == 3 {
+                ewkb_type |= EWKB_Z;
+            }
+            if code / 1000 == 2 || code / 1000 == 3 {
+                ewkb_type |= EWKB_M;
+            }
+            write_u32(&mut wkb, 1, little_endian, ewkb_type);
+            let mut srid_bytes = [0; 4];
+            write_u32(&mut srid_bytes, 0, little_endian, srid);
+            wkb.splice(5..5, srid_bytes.iter().cloned());
+            Ok(wkb)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{reproject_wkb, reproject_wkb_with_srid};
+    use crate::proj::{Proj, ProjError};
+
+    fn assert_almost_eq(a: f64, b: f64) {
+        let f: f64 = a / b;
+        assert!(f < 1.00001);
+        assert!(f > 0.99999);
+    }
+
+    fn ft_to_m() -> Proj {
+        Proj::new_known_crs("EPSG:2230", "EPSG:26946", None).unwrap()
+    }
+
+    fn f64_at(wkb: &[u8], offset: usize, little_endian: bool) -> f64 {
+        super::read_f64(wkb, offset, little_endian)
+    }
+
+    // A big-endian ISO WKB LINESTRING Z with two coordinates
+    fn linestring_z() -> Vec<u8> {
+        let mut wkb = vec![0];
+        wkb.extend_from_slice(&1002u32.to_be_bytes());
+        wkb.extend_from_slice(&2u32.to_be_bytes());
+        for &value in &[
+            4760096.421921,
+            3744293.729449,
+            10.0,
+            4760197.421921,
+            3744394.729449,
+            20.0,
+        ] {
+            wkb.extend_from_slice(&f64::to_be_bytes(value));
+        }
+        wkb
+    }
+
+    #[test]
+    fn test_reproject_iso_wkb() {
+        let wkb = linestring_z();
+        let result = reproject_wkb(&ft_to_m(), &wkb).unwrap();
+        assert_eq!(result.len(), wkb.len());
+        assert_eq!(&result[..9], &wkb[..9]);
+        assert_almost_eq(f64_at(&result, 9, false), 1450880.29);
+        assert_almost_eq(f64_at(&result, 17, false), 1141263.01);
+        let (_, _, z) = ft_to_m()
+            .convert_3d((4760096.421921, 3744293.729449, 10.0))
+            .unwrap();
+        assert_eq!(f64_at(&result, 25, false), z);
+        assert_almost_eq(f64_at(&result, 41, false), 1141293.79);
+    }
+
+    #[test]
+    fn test_reproject_ewkb_collection() {
+        // GEOMETRYCOLLECTION(POINT EMPTY, POINT M (4760096.421921 3744293.729449 5)), SRID 2230
+        let mut wkb = vec![1];
+        wkb.extend_from_slice(&(7 | 0x2000_0000u32).to_le_bytes());
+        wkb.extend_from_slice(&2230u32.to_le_bytes());
+        wkb.extend_from_slice(&2u32.to_le_bytes());
+        wkb.push(1);
+        wkb.extend_from_slice(&1u32.to_le_bytes());
+        wkb.extend_from_slice(&f64::NAN.to_le_bytes());
+        wkb.extend_from_slice(&f64::NAN.to_le_bytes());
+        wkb.push(1);
+        wkb.extend_from_slice(&(1 | 0x4000_0000u32).to_le_bytes());
+        for &value in &[4760096.421921f64, 3744293.729449, 5.0] {
+            wkb.extend_from_slice(&value.to_le_bytes());
+        }
+        let result = reproject_wkb(&ft_to_m(), &wkb).unwrap();
+        assert_eq!(&result[5..9], &26946u32.to_le_bytes());
+        assert!(f64_at(&result, 18, true).is_nan());
+        assert_almost_eq(f64_at(&result, 39, true), 1450880.29);
+        assert_eq!(f64_at(&result, 55, true), 5.0);
+    }
+
+    #[test]
+    fn test_set_srid() {
+        let wkb = linestring_z();
+        let ewkb = reproject_wkb_with_srid(&ft_to_m(), &wkb, Some(26946)).unwrap();
+        assert_eq!(ewkb.len(), wkb.len() + 4);
+        assert_eq!(
+            &ewkb[1..5],
+            &(2 | 0x8000_0000u32 | 0x2000_0000).to_be_bytes()
+        );
+        assert_eq!(&ewkb[5..9], &26946u32.to_be_bytes());
+        assert_almost_eq(f64_at(&ewkb, 13, false), 1450880.29);
+        // and remove it again, with an identity transformation
+        let identity = Proj::new("+proj=noop").unwrap();
+        let wkb = reproject_wkb_with_srid(&identity, &ewkb, None).unwrap();
+        assert_eq!(&wkb[1..5], &(2 | 0x8000_0000u32).to_be_bytes());
+        assert_eq!(f64_at(&wkb, 9, false), f64_at(&ewkb, 13, false));
+    }
+
+    #[test]
+    fn test_invalid_wkb() {
+        let wkb = linestring_z();
+        assert!(reproject_wkb(&ft_to_m(), &wkb[..wkb.len() - 1]).is_err());
+        assert!(reproject_wkb(&ft_to_m(), &[2, 1, 0, 0, 0]).is_err());
+        // CircularString
+        assert!(reproject_wkb(&ft_to_m(), &[1, 8, 0, 0, 0, 0, 0, 0, 0]).is_err());
+        // GeometryCollections, each containing the next
+        let nested = [1, 7, 0, 0, 0, 1, 0, 0, 0].repeat(10000);
+        match reproject_wkb(&ft_to_m(), &nested) {
+            Err(ProjError::Wkb(message)) => assert!(message.contains("nested")),
+            _ => panic!("expected the nesting to be rejected"),
+        }
+    }
+}
@@ -0,0 +1,411 @@
+use crate::proj::{Proj, ProjError};
+use crate::wkb::target_srid;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(String),
+    Open,
+    Close,
+    Comma,
+}
+
+fn tokenize(wkt: &str) -> Result<Vec<Token>, ProjError> {
+    let mut tokens = vec![];
+    let mut chars = wkt.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            ',' => tokens.push(Token::Comma),
+            c if c.is_whitespace() => (),
+            c if c.is_ascii_alphabetic() => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphabetic()) {
+                    word.push(c.to_ascii_uppercase());
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+                continue;
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' => {
+                let mut number = String::new();
+                while let Some(&c) = chars
+                    .peek()
+                    .filter(|c| c.is_ascii_alphanumeric() || "-+.".contains(**c))
+                {
+                    number.push(c);
+                    chars.next();
+                }
+                number
+                    .parse::<f64>()
+                    .map_err(|_| ProjError::Wkt(format!("invalid number {}", number)))?;
+                tokens.push(Token::Number(number));
+                continue;
+            }
+            c => return Err(ProjError::Wkt(format!("unexpected character {}", c))),
+        }
+        chars.next();
+    }
+    Ok(tokens)
+}
+
+/// The location of a coordinate within the tokens of a WKT geometry
+struct CoordRef {
+    index: usize,
+    has_z: bool,
+}
+
+/// The maximum nesting depth of geometry collections, which bounds the parser's recursion
+const MAX_DEPTH: usize = 32;
+
+/// A recursive descent WKT parser, recording the location of every coordinate
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    coords: Vec<CoordRef>,
+    depth: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Result<&Token, ProjError> {
+        self.pos += 1;
+        self.tokens
+            .get(self.pos - 1)
+            .ok_or_else(|| ProjError::Wkt("unexpected end of input".to_string()))
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ProjError> {
+        let token = self.next()?;
+        if *token == expected {
+            Ok(())
+        } else {
+            Err(ProjError::Wkt(format!(
+                "expected {:?}, found {:?}",
+                expected, token
+            )))
+        }
+    }
+
+    /// Consume the next token if it is `token`
+    fn accept(&mut self, token: &Token) -> bool {
+        let found = self.tokens.get(self.pos) == Some(token);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn accept_empty(&mut self) -> bool {
+        self.accept(&Token::Word("EMPTY".to_string()))
+    }
+
+    /// Parse a comma-separated list in parentheses, or `EMPTY`
+    fn list<F>(&mut self, mut item: F) -> Result<(), ProjError>
+    where
+        F: FnMut(&mut Self) -> Result<(), ProjError>,
+    {
+        if self.accept_empty() {
+            return Ok(());
+        }
+        self.expect(Token::Open)?;
+        item(self)?;
+        while self.accept(&Token::Comma) {
+            item(self)?;
+        }
+        self.expect(Token::Close)
+    }
+
+    /// Parse a coordinate, whose dimensions are given by `tag`, or by its number of ordinates
+    fn coord(&mut self, tag: &str) -> Result<(), ProjError> {
+        let index = self.pos;
+        let mut ordinates = 0;
+        while let Some(Token::Number(_)) = self.tokens.get(self.pos) {
+            self.pos += 1;
+            ordinates += 1;
+        }
+        let expected = match tag {
+            "" if (2..=4).contains(&ordinates) => ordinates,
+            "Z" | "M" => 3,
+            "ZM" => 4,
+            _ => 2,
+        };
+        if ordinates != expected {
+            return Err(ProjError::Wkt(format!(
+                "expected a coordinate with {} ordinates, found {}",
+                expected, ordinates
+            )));
+        }
+        let has_z = tag.contains('Z') || (tag.is_empty() && ordinates >= 3);
+        self.coords.push(CoordRef { index, has_z });
+        Ok(())
+    }
+
+    fn coords(&mut self, tag: &str) -> Result<(), ProjError> {
+        self.list(|parser| parser.coord(tag))
+    }
+
+    fn polygon(&mut self, tag: &str) -> Result<(), ProjError> {
+        self.list(|parser| parser.coords(tag))
+    }
+
+    fn collection(&mut self) -> Result<(), ProjError> {
+        if self.depth == MAX_DEPTH {
+            return Err(ProjError::Wkt(format!(
+                "geometry collections are nested more than {} deep",
+                MAX_DEPTH
+            )));
+        }
+        self.depth += 1;
+        self.list(Parser::geometry)?;
+        self.depth -= 1;
+        Ok(())
+    }
+
+    fn geometry(&mut self) -> Result<(), ProjError> {
+        let kind = match self.next()? {
+            Token::Word(kind) => kind.clone(),
+            token => return Err(ProjError::Wkt(format!("unexpected {:?}", token))),
+        };
+        let tag = match self.tokens.get(self.pos) {
+            Some(Token::Word(tag)) if tag == "Z" || tag == "M" || tag == "ZM" => {
+                self.pos += 1;
+                tag.clone()
+            }
+            _ => String::new(),
+        };
+        match kind.as_str() {
+            "POINT" => self.list(|parser| parser.coord(&tag)),
+            "LINESTRING" => self.coords(&tag),
+            "POLYGON" | "TRIANGLE" | "MULTILINESTRING" => self.polygon(&tag),
+            "MULTIPOLYGON" | "TIN" | "POLYHEDRALSURFACE" => {
+                self.list(|parser| parser.polygon(&tag))
+            }
+            // the points of a MultiPoint may or may not be in parentheses
+            "MULTIPOINT" => self.list(|parser| {
+                if let Some(Token::Number(_)) = parser.tokens.get(parser.pos) {
+                    parser.coord(&tag)
+                } else {
+                    parser.list(|parser| parser.coord(&tag))
+                }
+            }),
+            "GEOMETRYCOLLECTION" => self.collection(),
+            _ => Err(ProjError::Wkt(format!(
+                "unsupported geometry type {}",
+                kind
+            ))),
+        }
+    }
+}
+
+/// Split off the SRID of an EWKT geometry, e.g. `SRID=4326;POINT(1 2)`
+fn split_srid(wkt: &str) -> Result<(Option<u32>, &str), ProjError> {
+    let wkt = wkt.trim();
+    let has_srid = matches!(wkt.get(..5), Some(prefix) if prefix.eq_ignore_ascii_case("SRID="));
+    match wkt.find(';') {
+        Some(separator) if has_srid => {
+            let srid = &wkt[5..separator];
+            let srid = srid
+                .trim()
+                .parse()
+                .map_err(|_| ProjError::Wkt(format!("invalid SRID {}", srid)))?;
+            Ok((Some(srid), &wkt[separator + 1..]))
+        }
+        _ => Ok((None, wkt)),
+    }
+}
+
+/// Reproject a WKT or EWKT geometry
+///
+/// All the coordinates are transformed in the forward direction using `proj`, as
+/// [`Proj::convert`](struct.Proj.html#method.convert) does, and the geometry is re-serialised
+/// with upper-case keywords and single spaces. `z` coordinates are transformed along with
+/// `x` and `y`; `m` values are left unchanged.
+///
+/// If the input is EWKT with an SRID, e.g. `SRID=2230;POINT(1 2)`, the SRID is replaced by the EPSG
+/// code of the target CRS of `proj` (see [`Proj::target_epsg_code`](struct.Proj.html#method.target_epsg_code)),
+/// and reprojection fails if it has none.
+///
+/// Points, line strings, polygons, their multi-part counterparts, geometry collections,
+/// triangles, TINs and polyhedral surfaces are supported. Geometry collections may be nested
+/// at most 32 deep.
+///
+///```rust
+/// extern crate proj;
+/// use proj::{reproject_wkt, Proj};
+///
+/// let ft_to_m = Proj::new_known_crs("EPSG:2230", "EPSG:26946", None).unwrap();
+/// let result = reproject_wkt(&ft_to_m, "SRID=2230;POINT(4760096.421921 3744293.729449)").unwrap();
+/// assert!(result.starts_with("SRID=26946;POINT (1450880.29"));
+/// ```
+pub fn reproject_wkt(proj: &Proj, wkt: &str) -> Result<String, ProjError> {
+    reproject(proj, wkt, None)
+}
+
+/// Reproject a WKT or EWKT geometry, setting its SRID
+///
+/// As [`reproject_wkt`](fn.reproject_wkt.html), but the result is EWKT with the given SRID if
+/// `srid` is `Some`, and has no SRID otherwise.
+pub fn reproject_wkt_with_srid(
+    proj: &Proj,
+    wkt: &str,
+    srid: Option<u32>,
+) -> Result<String, ProjError> {
+    reproject(proj, wkt, Some(srid))
+}
+
+fn reproject(proj: &Proj, wkt: &str, srid: Option<Option<u32>>) -> Result<String, ProjError> {
+    let (input_srid, wkt) = split_srid(wkt)?;
+    let srid = match srid {
+        Some(srid) => srid,
+        None if input_srid.is_some() => Some(target_srid(proj)?),
+        None => None,
+    };
+    let mut parser = Parser {
+        tokens: tokenize(wkt)?,
+        pos: 0,
+        coords: vec![],
+        depth: 0,
+    };
+    parser.geometry()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(ProjError::Wkt(
+            "unexpected data after the geometry".to_string(),
+        ));
+    }
+
+    let mut tokens = parser.tokens;
+    let read = |tokens: &[Token], index: usize| match &tokens[index] {
+        Token::Number(number) => number.parse::<f64>().unwrap(),
+        _ => unreachable!(),
+    };
+    let mut points: Vec<[f64; 3]> = parser
+        .coords
+        .iter()
+        .map(|coord| {
+            let z = if coord.has_z {
+                read(&tokens, coord.index + 2)
+            } else {
+                0.0
+            };
+            [
+                read(&tokens, coord.index),
+                read(&tokens, coord.index + 1),
+                z,
+            ]
+        })
+        .collect();
+    proj.convert_array(&mut points)?;
+    for (coord, point) in parser.coords.iter().zip(&points) {
+        let dims = if coord.has_z { 3 } else { 2 };
+        for (i, value) in point.iter().take(dims).enumerate() {
+            tokens[coord.index + i] = Token::Number(value.to_string());
+        }
+    }
+
+    let mut result = match srid {
+        Some(srid) => format!("SRID={};", srid),
+        None => String::new(),
+    };
+    let mut previous: Option<&Token> = None;
+    for token in &tokens {
+        match (previous, token) {
+            (Some(Token::Word(_)), Token::Word(_))
+            | (Some(Token::Word(_)), Token::Open)
+            | (Some(Token::Number(_)), Token::Number(_))
+            | (Some(Token::Comma), _) => result.push(' '),
+            _ => (),
+        }
+        match token {
+            Token::Word(word) | Token::Number(word) => result.push_str(word),
+            Token::Open => result.push('('),
+            Token::Close => result.push(')'),
+            Token::Comma => result.push(','),
+        }
+        previous = Some(token);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{reproject_wkt, reproject_wkt_with_srid};
+    use crate::proj::{Proj, ProjError};
+
+    fn ft_to_m() -> Proj {
+        Proj::new_known_crs("EPSG:2230", "EPSG:26946", None).unwrap()
+    }
+
+    // Round the numbers of a WKT string to metres
+    fn rounded(wkt: &str) -> String {
+        wkt.split(' ')
+            .map(|word| {
+                let number = word
+                    .trim_start_matches('(')
+                    .trim_end_matches(&[')', ','][..]);
+                match number.parse::<f64>() {
+                    Ok(value) => word.replace(number, &format!("{:.0}", value)),
+                    Err(_) => word.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_reproject_wkt() {
+        let result = reproject_wkt(
+            &ft_to_m(),
+            "linestring(4760096.421921 3744293.729449,4760197.421921 3744394.729449)",
+        )
+        .unwrap();
+        assert_eq!(
+            rounded(&result),
+            "LINESTRING (1450880 1141263, 1450911 1141294)"
+        );
+    }
+
+    #[test]
+    fn test_reproject_ewkt() {
+        let result = reproject_wkt(
+            &ft_to_m(),
+            "SRID=2230;GEOMETRYCOLLECTION(POINT ZM (4760096.421921 3744293.729449 0 5), \
+             MULTIPOINT((4760096.421921 3744293.729449), EMPTY), POLYGON EMPTY)",
+        )
+        .unwrap();
+        assert_eq!(
+            rounded(&result),
+            "SRID=26946;GEOMETRYCOLLECTION (POINT ZM (1450880 1141263 0 5), \
+             MULTIPOINT ((1450880 1141263), EMPTY), POLYGON EMPTY)"
+        );
+    }
+
+    #[test]
+    fn test_set_srid() {
+        let wkt = "POINT M (4760096.421921 3744293.729449 7)";
+        let result = reproject_wkt_with_srid(&ft_to_m(), wkt, Some(26946)).unwrap();
+        assert_eq!(rounded(&result), "SRID=26946;POINT M (1450880 1141263 7)");
+        let result =
+            reproject_wkt_with_srid(&ft_to_m(), &format!("SRID=2230;{}", wkt), None).unwrap();
+        assert!(result.starts_with("POINT M (1450880.29"));
+    }
+
+    #[test]
+    fn test_invalid_wkt() {
+        assert!(reproject_wkt(&ft_to_m(), "POINT (1)").is_err());
+        assert!(reproject_wkt(&ft_to_m(), "POINT Z (1 2)").is_err());
+        assert!(reproject_wkt(&ft_to_m(), "LINESTRING (1 2, 3 4").is_err());
+        assert!(reproject_wkt(&ft_to_m(), "POINT (1 2) POINT (3 4)").is_err());
+        assert!(reproject_wkt(&ft_to_m(), "CIRCULARSTRING (1 2, 3 4, 5 6)").is_err());
+        assert!(reproject_wkt(&ft_to_m(), "POINT (1 x)").is_err());
+        let nested = format!(
+            "{}POINT (1 2){}",
+            "GEOMETRYCOLLECTION (".repeat(10000),
+            ")".repeat(10000)
+        );
+        match reproject_wkt(&ft_to_m(), &nested) {
+            Err(ProjError::Wkt(message)) => assert!(message.contains("nested")),
+            _ => panic!("expected the nesting to be rejected"),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Golden file tests of the `proj-rs` binary
+//!
+//! Each test transforms a file in `tests/data`, and compares the output to the file of the same
+//! name in `tests/golden`. Run with `UPDATE_GOLDEN=1` to overwrite the golden files instead.
+//! The output of the `info` and `search` subcommands depends on the version of the `PROJ`
+//! database, so only its key lines are checked.
+#![cfg(feature = "cli")]
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+fn path(dir: &str, name: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", dir, name]
+        .iter()
+        .collect()
+}
+
+fn proj_rs(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_proj-rs"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn assert_golden(output: &Output, golden: &str) {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let golden = path("golden", golden);
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&golden, &output.stdout).unwrap();
+    }
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        fs::read_to_string(golden).unwrap()
+    );
+}
+
+#[test]
+fn test_known_crs_with_header() {
+    let input = path("data", "nad83_ft.csv");
+    let output = proj_rs(
+        &[
+            "--from",
+            "EPSG:2230",
+            "--to",
+            "EPSG:26946",
+            "--header",
+            "--columns",
+            "x,y",
+            "--precision",
+            "2",
+            input.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert_golden(&output, "nad83_ft_to_m.csv");
+}
+
+#[test]
+fn test_geographic_tab_delimited() {
+    let input = path("data", "lonlat.tsv");
+    let output = proj_rs(
+        &[
+            "-f",
+            "EPSG:4326",
+            "-t",
+            "EPSG:3857",
+            "-d",
+            "tab",
+            "--precision",
+            "2",
+            input.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert_golden(&output, "lonlat_to_web_mercator.tsv");
+}
+
+#[test]
+fn test_pipeline_from_stdin() {
+    let input = fs::read_to_string(path("data", "us_ft.txt")).unwrap();
+    let output = proj_rs(
+        &[
+            "--pipeline",
+            "+proj=pipeline +step +proj=unitconvert +xy_in=us-ft +xy_out=m",
+            "--delimiter",
+            " ",
+            "--precision",
+            "3",
+        ],
+        &input,
+    );
+    assert_golden(&output, "us_ft_pipeline.txt");
+}
+
+#[test]
+fn test_on_error() {
+    let input = path("data", "invalid.csv");
+    let args = |on_error| {
+        vec![
+            "--from",
+            "EPSG:2230",
+            "--to",
+            "EPSG:26946",
+            "--precision",
+            "2",
+            "--on-error",
+            on_error,
+            input.to_str().unwrap(),
+        ]
+    };
+    let output = proj_rs(&args("skip"), "");
+    assert_golden(&output, "invalid_skip.csv");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("line 2: invalid coordinate \"north\" in column 1"));
+    assert!(stderr.contains("line 3: there is no column 2"));
+
+    assert_golden(&proj_rs(&args("keep"), ""), "invalid_keep.csv");
+
+    let output = proj_rs(&args("fail"), "");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("line 2"));
+}
+
+#[test]
+fn test_usage_errors() {
+    assert!(!proj_rs(&["--from", "EPSG:4326"], "").status.success());
+    let output = proj_rs(&["--from", "EPSG:4326", "--to", "EPSG:nonsense"], "");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no transformation"));
+    let output = proj_rs(&["--pipeline", "+proj=noop", "--columns", "x,y"], "1,2\n");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("header"));
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn test_info() {
+    let info = stdout(&proj_rs(&["info", "EPSG:32633"], ""));
+    assert!(info.contains("Name: WGS 84 / UTM zone 33N\n"));
+    assert!(info.contains("Type: Projected CRS\n"));
+    assert!(info.contains("Bounds: west 12, south 0, east 18, north 84\n"));
+    assert!(info.contains("  Easting (E): east, metre\n"));
+    assert!(info.contains("  Northing (N): north, metre\n"));
+    assert!(info.contains("WKT:\nPROJCRS[\"WGS 84 / UTM zone 33N\""));
+
+    let info = stdout(&proj_rs(&["info", "EPSG:4326", "--format", "projjson"], ""));
+    assert!(info.contains("  Geodetic latitude (Lat): north, degree\n"));
+    assert!(info.contains("PROJJSON:\n{"));
+    assert!(!info.contains("WKT:"));
+}
+
+#[test]
+fn test_info_operations() {
+    let info = stdout(&proj_rs(
+        &[
+            "info",
+            "EPSG:4267",
+            "--to",
+            "EPSG:4269",
+            "--area=-102.05,36.99,-94.58,40.01",
+            "--format",
+            "none",
+        ],
+        "",
+    ));
+    assert!(info.contains("Name: NAD27\n"));
+    assert!(info.contains("Candidate operations from EPSG:4267 to EPSG:4269:\n1. "));
+    assert!(info.contains("NAD27 to NAD83"));
+    assert!(info.contains("   Accuracy: "));
+    assert!(info.contains("   PROJ string: +proj=pipeline"));
+
+    let output = proj_rs(&["info", "EPSG:4267", "--area", "1,2,3"], "");
+    assert!(!output.status.success());
+    let output = proj_rs(&["info", "EPSG:nonsense"], "");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("isn't a CRS"));
+}
+
+#[test]
+fn test_search() {
+    let found = stdout(&proj_rs(&["search", "UTM 33"], ""));
+    assert!(found
+        .lines()
+        .any(|line| line.starts_with("EPSG:32633 ") && line.ends_with(" WGS 84 / UTM zone 33N")));
+    assert!(!found.contains("(deprecated)"));
+
+    // the words of the query may be given as separate arguments
+    let found = stdout(&proj_rs(
+        &[
+            "search",
+            "--type",
+            "geographic",
+            "--authority",
+            "EPSG",
+            "WGS",
+            "84",
+        ],
+        "",
+    ));
+    assert!(found.lines().any(|line| line.starts_with("EPSG:4326 ")));
+    assert!(found.lines().all(|line| line.contains("Geographic")));
+
+    let output = proj_rs(&["search", "no such CRS"], "");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no CRS matches \"no such CRS\""));
+}